@@ -21,6 +21,7 @@ fn main() {
             resizable: true,
         },
         vsync: Vsync::Disabled,
+        srgb: false,
     });
 }
 
@@ -26,6 +26,7 @@ fn main() {
             resizable: true,
         },
         vsync: Vsync::Disabled,
+        srgb: false,
     });
 }
 
@@ -20,6 +20,7 @@ fn main() {
             resizable: true,
         },
         vsync: Vsync::Disabled,
+        srgb: false,
     });
 }
 
@@ -24,6 +24,7 @@ fn main() {
             resizable: true,
         },
         vsync: Vsync::Disabled,
+        srgb: false,
     });
 }
 
@@ -111,7 +112,7 @@ impl App for TextureApp {
                             Err(error) => log::warn!("Error {}: {:?}", asset.relative_path, error),
                         }
                     }
-                })
+                });
             }
             KeyboardButton::A => {
                 log::info!("A Read");
@@ -124,7 +125,7 @@ impl App for TextureApp {
                             Err(error) => log::warn!("Error {}: {:?}", asset.relative_path, error),
                         }
                     }
-                })
+                });
             }
             _ => {}
         }
@@ -15,6 +15,7 @@ fn main() {
             resizable: true,
         },
         vsync: Vsync::Disabled,
+        srgb: false,
     });
 }
 
@@ -1,41 +1,120 @@
-use super::LoaderError;
+use super::{ErrorDetail, Loadable, LoaderError, RequestHandle};
 use crate::{App, Context};
 use alloc::{
     boxed::Box,
+    rc::Rc,
     string::{String, ToString},
+    vec,
     vec::Vec,
 };
+use core::cell::RefCell;
 
-/// Represents a binary blob loaded from an external source.
+/// Represents a resource loaded from an external source. By default this is the binary blob that
+/// was read, but typed loads decode the blob into `T` first.
 #[derive(Clone, Debug)]
-pub struct Asset {
+pub struct Asset<T = Vec<u8>> {
     /// The path used to query for this asset.
     pub relative_path: String,
-    /// Either the contents of the asset as bytes, or an error.
-    pub result: Result<Vec<u8>, LoaderError>,
+    /// Either the contents of the asset, or an error.
+    pub result: Result<T, LoaderError>,
+    /// More context on the error, if the asset failed to load and any is available.
+    pub detail: Option<ErrorDetail>,
 }
 
-impl Asset {
+impl<T> Asset<T> {
     /// Creates a new asset that successfully loaded.
-    pub fn new_ok(relative_path: String, contents: Vec<u8>) -> Asset {
+    pub fn new_ok(relative_path: String, contents: T) -> Asset<T> {
         Asset {
             relative_path,
             result: Ok(contents),
+            detail: None,
         }
     }
 
     /// Creates a new asset that failed to load.
-    pub fn new_err(relative_path: String, error: LoaderError) -> Asset {
+    pub fn new_err(relative_path: String, error: LoaderError) -> Asset<T> {
         Asset {
             relative_path,
             result: Err(error),
+            detail: None,
         }
     }
+
+    /// Fails the asset with the given error and detail.
+    pub(crate) fn fail(&mut self, error: LoaderError, detail: ErrorDetail) {
+        self.result = Err(error);
+        self.detail = Some(detail);
+    }
+}
+
+/// Callback shared by every asset redelivered while watching for changes.
+pub(crate) type AssetWatchCallback<A> = Rc<RefCell<dyn FnMut(&mut Context<A>, &mut A, Asset) + 'static>>;
+
+/// Handles the contents of a request once every asset in it has been read.
+pub(crate) trait AssetHandler<A: App> {
+    /// Called on the asset thread after all assets have been read.
+    fn decode(&mut self, _assets: &mut Vec<Asset>) {}
+
+    /// Called on the main thread with the assets from the request.
+    fn call(&mut self, ctx: &mut Context<A>, app: &mut A, assets: Vec<Asset>);
+}
+
+/// Passes the binary blobs straight through to the callback.
+struct RawHandler<C> {
+    callback: C,
+}
+
+impl<A: App, C: FnMut(&mut Context<A>, &mut A, Vec<Asset>)> AssetHandler<A> for RawHandler<C> {
+    fn call(&mut self, ctx: &mut Context<A>, app: &mut A, assets: Vec<Asset>) {
+        (self.callback)(ctx, app, assets)
+    }
+}
+
+/// Decodes each binary blob into `T` before passing them to the callback.
+struct TypedHandler<T, C> {
+    decoded: Vec<Asset<T>>,
+    callback: C,
+}
+
+impl<A: App, T: Loadable, C: FnMut(&mut Context<A>, &mut A, Vec<Asset<T>>)> AssetHandler<A>
+    for TypedHandler<T, C>
+{
+    fn decode(&mut self, assets: &mut Vec<Asset>) {
+        self.decoded = assets
+            .drain(..)
+            .map(|asset| match asset.result {
+                Ok(contents) => match T::decode(contents) {
+                    Ok(value) => Asset::new_ok(asset.relative_path, value),
+                    Err(error) => {
+                        let message = alloc::format!("Failed to decode as {}", core::any::type_name::<T>());
+                        Asset {
+                            relative_path: asset.relative_path,
+                            result: Err(error),
+                            detail: Some(ErrorDetail::new(message, None)),
+                        }
+                    }
+                },
+                Err(error) => Asset {
+                    relative_path: asset.relative_path,
+                    result: Err(error),
+                    detail: asset.detail,
+                },
+            })
+            .collect();
+    }
+
+    fn call(&mut self, ctx: &mut Context<A>, app: &mut A, _assets: Vec<Asset>) {
+        let decoded = core::mem::take(&mut self.decoded);
+        (self.callback)(ctx, app, decoded)
+    }
 }
 
 pub(crate) struct AssetRequest<A: App> {
     pub assets: Vec<Asset>,
-    pub callback: Box<dyn FnMut(&mut Context<A>, &mut A, Vec<Asset>) + 'static>,
+    /// Higher priority requests are read first.
+    pub priority: i32,
+    pub progress: RequestHandle,
+    handler: Box<dyn AssetHandler<A> + 'static>,
 }
 
 unsafe impl<A: App> Send for AssetRequest<A> {}
@@ -46,15 +125,69 @@ impl<A: App> AssetRequest<A> {
         callback: C,
     ) -> AssetRequest<A> {
         AssetRequest {
-            assets: relative_paths
-                .into_iter()
-                .map(|path| Asset::new_err(path.as_ref().to_string(), LoaderError::Pending))
-                .collect::<Vec<Asset>>(),
-            callback: Box::new(callback),
+            assets: pending(relative_paths),
+            priority: 0,
+            progress: RequestHandle::new(relative_paths.len()),
+            handler: Box::new(RawHandler {
+                callback,
+            }),
         }
     }
 
+    pub(crate) fn typed<T: Loadable, C: FnMut(&mut Context<A>, &mut A, Vec<Asset<T>>) + 'static>(
+        relative_paths: &[impl AsRef<str>],
+        callback: C,
+    ) -> AssetRequest<A> {
+        AssetRequest {
+            assets: pending(relative_paths),
+            priority: 0,
+            progress: RequestHandle::new(relative_paths.len()),
+            handler: Box::new(TypedHandler {
+                decoded: Vec::new(),
+                callback,
+            }),
+        }
+    }
+
+    /// Creates an already completed request for an asset that changed since it was last read.
+    pub(crate) fn reloaded(asset: Asset, callback: &AssetWatchCallback<A>) -> AssetRequest<A> {
+        let callback = callback.clone();
+        AssetRequest {
+            assets: vec![asset],
+            priority: 0,
+            progress: RequestHandle::new(1),
+            handler: Box::new(RawHandler {
+                callback: move |ctx: &mut Context<A>, app: &mut A, mut assets: Vec<Asset>| {
+                    if let Some(asset) = assets.pop() {
+                        (callback.borrow_mut())(ctx, app, asset);
+                    }
+                },
+            }),
+        }
+    }
+
+    pub(crate) fn with_priority(mut self, priority: i32) -> AssetRequest<A> {
+        self.priority = priority;
+        self
+    }
+
+    /// Decodes the read assets. This is expensive, and is expected to be called off the main thread
+    /// where possible.
+    pub(crate) fn decode(&mut self) {
+        self.handler.decode(&mut self.assets)
+    }
+
+    /// Calls the request's callback, unless the request was cancelled.
     pub(crate) fn call(mut self, ctx: &mut Context<A>, app: &mut A) {
-        (self.callback)(ctx, app, self.assets)
+        if !self.progress.is_cancelled() {
+            self.handler.call(ctx, app, self.assets)
+        }
     }
 }
+
+fn pending(relative_paths: &[impl AsRef<str>]) -> Vec<Asset> {
+    relative_paths
+        .iter()
+        .map(|path| Asset::new_err(path.as_ref().to_string(), LoaderError::Pending))
+        .collect::<Vec<Asset>>()
+}
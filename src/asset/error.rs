@@ -1,3 +1,35 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+use std::io;
+
+/// Context for why an asset failed to load, beyond its `LoaderError` kind.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ErrorDetail {
+    /// The underlying error message, such as the one reported by the OS.
+    pub message: String,
+    /// Where the asset was looked for. This is a file path, a pack entry, the list of mounts that
+    /// were tried, or a url. None if the failure isn't tied to a location, like failing to decode.
+    pub source: Option<String>,
+}
+
+impl ErrorDetail {
+    pub(crate) fn new(message: impl ToString, source: Option<String>) -> ErrorDetail {
+        ErrorDetail {
+            message: message.to_string(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{} ({})", self.message, source),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
 /// A list specifying general categories of I/O error.
 ///
 /// This list is intended to grow over time and it is not recommended to
@@ -91,3 +123,30 @@ pub enum LoaderError {
     /// New `ErrorKind`s might be added in the future for some of those.
     Other,
 }
+
+impl From<io::ErrorKind> for LoaderError {
+    fn from(error: io::ErrorKind) -> Self {
+        match error {
+            io::ErrorKind::NotFound => LoaderError::NotFound,
+            io::ErrorKind::PermissionDenied => LoaderError::PermissionDenied,
+            io::ErrorKind::ConnectionRefused => LoaderError::ConnectionRefused,
+            io::ErrorKind::ConnectionReset => LoaderError::ConnectionReset,
+            io::ErrorKind::ConnectionAborted => LoaderError::ConnectionAborted,
+            io::ErrorKind::NotConnected => LoaderError::NotConnected,
+            io::ErrorKind::AddrInUse => LoaderError::AddrInUse,
+            io::ErrorKind::AddrNotAvailable => LoaderError::AddrNotAvailable,
+            io::ErrorKind::BrokenPipe => LoaderError::BrokenPipe,
+            io::ErrorKind::AlreadyExists => LoaderError::AlreadyExists,
+            io::ErrorKind::WouldBlock => LoaderError::WouldBlock,
+            io::ErrorKind::InvalidInput => LoaderError::InvalidInput,
+            io::ErrorKind::InvalidData => LoaderError::InvalidData,
+            io::ErrorKind::TimedOut => LoaderError::TimedOut,
+            io::ErrorKind::WriteZero => LoaderError::WriteZero,
+            io::ErrorKind::Interrupted => LoaderError::Interrupted,
+            io::ErrorKind::Unsupported => LoaderError::Unsupported,
+            io::ErrorKind::UnexpectedEof => LoaderError::UnexpectedEof,
+            io::ErrorKind::OutOfMemory => LoaderError::OutOfMemory,
+            _ => LoaderError::Other,
+        }
+    }
+}
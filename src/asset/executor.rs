@@ -0,0 +1,223 @@
+use super::{Asset, AssetRequest, AssetStateContract, Loadable};
+use crate::{App, Context};
+use alloc::{boxed::Box, rc::Rc, sync::Arc, task::Wake, vec::Vec};
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context as TaskContext, Poll, Waker};
+
+/// Called on the main thread with the output of a finished task.
+type Completion<A> = Box<dyn FnOnce(&mut Context<A>, &mut A) + 'static>;
+
+/// Marks a task as ready to be polled again.
+struct TaskWaker {
+    woken: AtomicBool,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.woken.store(true, Ordering::Relaxed);
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Relaxed);
+    }
+}
+
+struct Task<A: App> {
+    future: Pin<Box<dyn Future<Output = Completion<A>> + 'static>>,
+    waker: Arc<TaskWaker>,
+}
+
+/// Polls spawned tasks on the main thread. Tasks are only polled after they've been woken, which
+/// happens when a read they're waiting on completes.
+pub(crate) struct Executor<A: App> {
+    tasks: Vec<Task<A>>,
+    /// Requests made through readers, waiting to be passed to the asset state.
+    requests: Rc<RefCell<Vec<AssetRequest<A>>>>,
+}
+
+impl<A: App> Executor<A> {
+    pub(crate) fn new() -> Executor<A> {
+        Executor {
+            tasks: Vec::new(),
+            requests: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+}
+
+struct ReadState<T> {
+    assets: Option<Vec<Asset<T>>>,
+    waker: Option<Waker>,
+}
+
+/// Future that resolves to the assets from a read once all of them have completed.
+pub struct ReadFuture<T = Vec<u8>> {
+    state: Rc<RefCell<ReadState<T>>>,
+}
+
+impl<T> Future for ReadFuture<T> {
+    type Output = Vec<Asset<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+        match state.assets.take() {
+            Some(assets) => Poll::Ready(assets),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Handle for making reads from inside spawned tasks, where the context isn't available. Reads
+/// start at the end of the current frame, whether or not the returned future is awaited.
+pub struct AssetReader<A: App> {
+    requests: Rc<RefCell<Vec<AssetRequest<A>>>>,
+}
+
+impl<A: App> Clone for AssetReader<A> {
+    fn clone(&self) -> Self {
+        AssetReader {
+            requests: self.requests.clone(),
+        }
+    }
+}
+
+impl<A: App> AssetReader<A> {
+    /// Requests a read of a set of assets. The returned future resolves once all assets requested
+    /// have completed.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** The path is relative to the current working directory.
+    /// - **Web:** The path is relative to the current url's root.
+    pub fn read(&self, relative_paths: &[impl AsRef<str>]) -> ReadFuture {
+        let (state, future) = read_state();
+        self.requests
+            .borrow_mut()
+            .push(AssetRequest::new(relative_paths, move |_ctx, _app, assets| resolve(&state, assets)));
+        future
+    }
+
+    /// Requests a read of a set of assets, decoding each into `T`. The returned future resolves
+    /// once all assets requested have completed. Decoding failures are reported as
+    /// `LoaderError::InvalidData`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** The path is relative to the current working directory. Decoding happens on
+    ///   the asset thread.
+    /// - **Web:** The path is relative to the current url's root. Decoding happens on the main
+    ///   thread.
+    pub fn load<T: Loadable>(&self, relative_paths: &[impl AsRef<str>]) -> ReadFuture<T> {
+        let (state, future) = read_state();
+        self.requests
+            .borrow_mut()
+            .push(AssetRequest::typed(relative_paths, move |_ctx, _app, assets| resolve(&state, assets)));
+        future
+    }
+}
+
+fn read_state<T>() -> (Rc<RefCell<ReadState<T>>>, ReadFuture<T>) {
+    let state = Rc::new(RefCell::new(ReadState {
+        assets: None,
+        waker: None,
+    }));
+    let future = ReadFuture {
+        state: state.clone(),
+    };
+    (state, future)
+}
+
+fn resolve<T>(state: &Rc<RefCell<ReadState<T>>>, assets: Vec<Asset<T>>) {
+    let mut state = state.borrow_mut();
+    state.assets = Some(assets);
+    if let Some(waker) = state.waker.take() {
+        waker.wake();
+    }
+}
+
+/// Task related functions.
+impl<A: App> Context<A> {
+    /// Gets a handle for making reads from inside spawned tasks.
+    pub fn reader(&mut self) -> AssetReader<A> {
+        AssetReader {
+            requests: self.executor().requests.clone(),
+        }
+    }
+
+    /// Spawns a task on the main thread. The task is polled at the end of the frame, and again
+    /// whenever a read it's waiting on completes. Once the task finishes, its output is passed to
+    /// the callback.
+    ///
+    /// # Example
+    /// ```
+    /// use storm::{App, Context};
+    ///
+    /// fn load_level<A: App>(ctx: &mut Context<A>) {
+    ///     let reader = ctx.reader();
+    ///     ctx.spawn(
+    ///         async move {
+    ///             let manifest = reader.load::<String>(&["level.txt"]).await;
+    ///             let paths: Vec<String> = match &manifest[0].result {
+    ///                 Ok(manifest) => manifest.lines().map(String::from).collect(),
+    ///                 Err(_) => Vec::new(),
+    ///             };
+    ///             reader.read(&paths).await
+    ///         },
+    ///         |_ctx, _app, assets| {
+    ///             // Use the assets listed in the manifest.
+    ///         },
+    ///     );
+    /// }
+    /// ```
+    pub fn spawn<
+        T: 'static,
+        F: Future<Output = T> + 'static,
+        C: FnMut(&mut Context<A>, &mut A, T) + 'static,
+    >(
+        &mut self,
+        future: F,
+        mut callback: C,
+    ) {
+        let future = async move {
+            let output = future.await;
+            Box::new(move |ctx: &mut Context<A>, app: &mut A| callback(ctx, app, output)) as Completion<A>
+        };
+        self.executor().tasks.push(Task {
+            future: Box::pin(future),
+            waker: Arc::new(TaskWaker {
+                woken: AtomicBool::new(true),
+            }),
+        });
+    }
+
+    /// Polls every woken task, then starts the reads they requested.
+    pub(crate) fn poll_tasks(&mut self, app: &mut A) {
+        let mut tasks = core::mem::take(&mut self.executor().tasks);
+        tasks.retain_mut(|task| {
+            if !task.waker.woken.swap(false, Ordering::Relaxed) {
+                return true;
+            }
+            let waker = Waker::from(task.waker.clone());
+            match task.future.as_mut().poll(&mut TaskContext::from_waker(&waker)) {
+                Poll::Ready(completion) => {
+                    completion(self, app);
+                    false
+                }
+                Poll::Pending => true,
+            }
+        });
+        // Tasks spawned while polling are polled next frame.
+        tasks.append(&mut self.executor().tasks);
+        self.executor().tasks = tasks;
+
+        let requests = core::mem::take(&mut *self.executor().requests.borrow_mut());
+        for request in requests {
+            self.assets().read(request);
+        }
+    }
+}
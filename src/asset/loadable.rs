@@ -0,0 +1,62 @@
+use super::LoaderError;
+use crate::audio::Sound;
+use crate::color::RGBA8;
+use crate::image::Image;
+use alloc::{string::String, vec::Vec};
+use fontdue::{Font, FontSettings};
+
+/// A type that can be decoded from the contents of an asset. Decoding happens on the asset thread
+/// where the platform allows it, so only cheap work, like uploading to the GPU, is left for the
+/// main thread.
+///
+/// # Example
+/// ```
+/// use storm::asset::*;
+///
+/// pub struct Level {
+///     pub tiles: Vec<u8>,
+/// }
+///
+/// impl Loadable for Level {
+///     fn decode(contents: Vec<u8>) -> Result<Self, LoaderError> {
+///         Ok(Level {
+///             tiles: contents,
+///         })
+///     }
+/// }
+/// ```
+pub trait Loadable: Send + Sized + 'static {
+    /// Decodes the contents of the asset. Return `LoaderError::InvalidData` if the contents are
+    /// malformed.
+    fn decode(contents: Vec<u8>) -> Result<Self, LoaderError>;
+}
+
+impl Loadable for Vec<u8> {
+    fn decode(contents: Vec<u8>) -> Result<Self, LoaderError> {
+        Ok(contents)
+    }
+}
+
+impl Loadable for String {
+    fn decode(contents: Vec<u8>) -> Result<Self, LoaderError> {
+        String::from_utf8(contents).map_err(|_| LoaderError::InvalidData)
+    }
+}
+
+impl Loadable for Image<RGBA8> {
+    fn decode(contents: Vec<u8>) -> Result<Self, LoaderError> {
+        Image::from_bytes(&contents).map_err(|_| LoaderError::InvalidData)
+    }
+}
+
+impl Loadable for Sound {
+    fn decode(contents: Vec<u8>) -> Result<Self, LoaderError> {
+        Sound::from_bytes(&contents).map_err(|_| LoaderError::InvalidData)
+    }
+}
+
+impl Loadable for Font {
+    fn decode(contents: Vec<u8>) -> Result<Self, LoaderError> {
+        Font::from_bytes(contents, FontSettings::default()).map_err(|_| LoaderError::InvalidData)
+    }
+}
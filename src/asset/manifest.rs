@@ -0,0 +1,167 @@
+use super::{normalize_path, Asset, ErrorDetail, Loadable, LoaderError};
+use alloc::{string::String, vec::Vec};
+use hashbrown::HashMap;
+
+/// Named groups of asset paths, used to preload and unload related assets together.
+///
+/// Manifests are plain text. A `[name]` line starts a group, and every following non-empty line
+/// until the next group is a path in that group. Lines starting with `#` are comments. Groups with
+/// the same name are merged.
+///
+/// A path may be followed by `crc32=` and the hex CRC-32 of its contents. Reads of that path are
+/// verified against it once the manifest is set, and mismatched contents fail with
+/// `LoaderError::InvalidData` instead of being decoded.
+///
+/// ```text
+/// # Shared by every level.
+/// [common]
+/// textures/ui.png crc32=8a9136aa
+///
+/// [level_3]
+/// textures/level_3.png
+/// sounds/level_3.flac
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    groups: HashMap<String, Vec<String>>,
+    checksums: HashMap<String, u32>,
+}
+
+impl Manifest {
+    /// Parses a manifest. Paths are normalized, and a path outside of any group, one that would
+    /// escape the root, or a malformed checksum fails with `LoaderError::InvalidData`.
+    pub fn parse(source: &str) -> Result<Manifest, LoaderError> {
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        let mut checksums = HashMap::new();
+        let mut current: Option<String> = None;
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                let name = String::from(name.trim());
+                groups.entry(name.clone()).or_default();
+                current = Some(name);
+                continue;
+            }
+            let group = current.as_ref().ok_or(LoaderError::InvalidData)?;
+            let (path, checksum) = match line.rsplit_once(char::is_whitespace) {
+                Some((path, checksum)) if checksum.starts_with("crc32=") => {
+                    let checksum = u32::from_str_radix(&checksum["crc32=".len()..], 16)
+                        .map_err(|_| LoaderError::InvalidData)?;
+                    (path.trim_end(), Some(checksum))
+                }
+                _ => (line, None),
+            };
+            let path = normalize_path(path).map_err(|_| LoaderError::InvalidData)?;
+            if path.is_empty() {
+                return Err(LoaderError::InvalidData);
+            }
+            if let Some(checksum) = checksum {
+                checksums.insert(path.clone(), checksum);
+            }
+            let paths = groups.get_mut(group).unwrap();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        Ok(Manifest {
+            groups,
+            checksums,
+        })
+    }
+
+    /// Gets the paths in a group, in the order they were listed.
+    pub fn group(&self, name: &str) -> Option<&[String]> {
+        self.groups.get(name).map(|paths| paths.as_slice())
+    }
+
+    /// Iterates over the names of every group.
+    pub fn group_names(&self) -> impl Iterator<Item = &str> {
+        self.groups.keys().map(|name| name.as_str())
+    }
+
+    /// Gets the expected CRC-32 of a path's contents, if the manifest lists one.
+    pub fn checksum(&self, relative_path: &str) -> Option<u32> {
+        self.checksums.get(relative_path).copied()
+    }
+
+    pub(crate) fn checksums(&self) -> &HashMap<String, u32> {
+        &self.checksums
+    }
+}
+
+/// Computes the CRC-32 of the contents, as used by manifest checksums.
+pub fn crc32(contents: &[u8]) -> u32 {
+    crc32fast::hash(contents)
+}
+
+/// Fails the asset with `LoaderError::InvalidData` if it loaded, but its contents don't match the
+/// expected checksum for its path.
+pub(crate) fn verify(asset: &mut Asset, checksums: &HashMap<String, u32>) {
+    let contents = match &asset.result {
+        Ok(contents) => contents,
+        Err(_) => return,
+    };
+    let expected = match normalize_path(&asset.relative_path).ok().and_then(|path| checksums.get(&path)) {
+        Some(expected) => *expected,
+        None => return,
+    };
+    let actual = crc32(contents);
+    if actual != expected {
+        let message = alloc::format!(
+            "Checksum mismatch, expected crc32={:08x} but read crc32={:08x}",
+            expected,
+            actual
+        );
+        asset.fail(LoaderError::InvalidData, ErrorDetail::new(message, None));
+    }
+}
+
+impl Loadable for Manifest {
+    fn decode(contents: Vec<u8>) -> Result<Self, LoaderError> {
+        let source = String::from_utf8(contents).map_err(|_| LoaderError::InvalidData)?;
+        Manifest::parse(&source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let manifest = Manifest::parse(
+            "# comment\n[common]\n  ./textures/ui.png\n\n[level_3]\nsounds\\music.flac\n[common]\ntextures/ui.png\nfont.ttf\n[empty]\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.group("common").unwrap(), ["textures/ui.png", "font.ttf"]);
+        assert_eq!(manifest.group("level_3").unwrap(), ["sounds/music.flac"]);
+        assert!(manifest.group("empty").unwrap().is_empty());
+        assert_eq!(manifest.group("missing"), None);
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        let manifest = Manifest::parse("[group]\nlevel one.txt   crc32=CBF43926\nplain.txt").unwrap();
+        assert_eq!(manifest.group("group").unwrap(), ["level one.txt", "plain.txt"]);
+        assert_eq!(manifest.checksum("level one.txt"), Some(0xcbf43926));
+        assert_eq!(manifest.checksum("plain.txt"), None);
+
+        let mut asset = Asset::new_ok(String::from("./level one.txt"), b"123456789".to_vec());
+        verify(&mut asset, manifest.checksums());
+        assert!(asset.result.is_ok());
+        let mut asset = Asset::new_ok(String::from("level one.txt"), b"12345678".to_vec());
+        verify(&mut asset, manifest.checksums());
+        assert_eq!(asset.result, Err(LoaderError::InvalidData));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Manifest::parse("[group]\na.png crc32=xyz"), Err(LoaderError::InvalidData));
+        assert_eq!(Manifest::parse("orphan.png\n[group]"), Err(LoaderError::InvalidData));
+        assert_eq!(Manifest::parse("[group]\n../escape.png"), Err(LoaderError::InvalidData));
+    }
+}
@@ -12,40 +12,189 @@ pub(crate) use wasm::AssetState;
 
 mod asset;
 mod error;
+mod executor;
+mod loadable;
+mod manifest;
+mod progress;
+mod server;
+mod storage;
+mod vfs;
 
 pub use self::asset::Asset;
-pub use self::error::LoaderError;
+pub use self::error::{ErrorDetail, LoaderError};
+pub use self::executor::{AssetReader, ReadFuture};
+pub use self::loadable::Loadable;
+pub use self::manifest::{crc32, Manifest};
+pub use self::progress::RequestHandle;
+pub use self::server::{AssetStatus, Handle};
+pub use self::vfs::{build_pack, normalize_path, MountSource};
 
-pub(crate) use self::asset::AssetRequest;
+pub(crate) use self::asset::{AssetRequest, AssetWatchCallback};
+pub(crate) use self::executor::Executor;
+pub(crate) use self::server::AssetServer;
+pub(crate) use self::storage::StorageRequest;
 
 use crate::{App, Context};
+use alloc::{rc::Rc, string::String, vec::Vec};
+use core::cell::RefCell;
+use hashbrown::HashMap;
 
 pub(crate) trait AssetStateContract<A: App> {
     /// Creates a new asset state.
     fn init() -> Self;
 
-    /// Pushes a read request to the queue. Relative to the current working directory.
+    /// Pushes a read request to the queue. Relative to the root of the virtual filesystem.
     fn read(&mut self, request: AssetRequest<A>);
 
-    /// Processes all available completed read requests.
+    /// Processes all available completed read requests, including assets that changed while being
+    /// watched.
     fn next(&mut self) -> Option<AssetRequest<A>>;
+
+    /// Sets the callback changed assets are redelivered to. None disables watching.
+    fn watch(&mut self, callback: Option<AssetWatchCallback<A>>);
+
+    /// Attaches a source to the virtual filesystem at the given virtual path.
+    fn mount(&mut self, mount_point: &str, source: MountSource) -> Result<(), LoaderError>;
+
+    /// Removes every source from the virtual filesystem.
+    fn unmount_all(&mut self);
+
+    /// Pushes an operation on the save directory to the queue. Operations are applied in the order
+    /// they're pushed.
+    fn storage(&mut self, request: StorageRequest<A>);
+
+    /// Keeps the contents of a normalized path in memory. Reads of the path are served from memory
+    /// until it's evicted.
+    fn cache(&mut self, relative_path: String, contents: Vec<u8>);
+
+    /// Removes a normalized path from memory.
+    fn evict(&mut self, relative_path: &str);
+
+    /// Sets the checksums read assets are verified against, keyed by normalized path.
+    fn set_checksums(&mut self, checksums: HashMap<String, u32>);
 }
 
 /// Asset related functions.
 impl<A: App> Context<A> {
     /// Requests a read of a set of assets. This produces an AssetRead event with the result of the
-    /// read once all assets requested have completed.
+    /// read once all assets requested have completed. The returned handle reports progress and can
+    /// cancel the request.
     ///
     /// ## Platform-specific
     ///
     /// - **Non-web:** The path is relative to the current working directory.
     /// - **Web:** The path is relative to the current url's root.
-    pub fn read<C: FnMut(&mut Context<A>, &mut A, alloc::vec::Vec<Asset>) + 'static>(
+    pub fn read<C: FnMut(&mut Context<A>, &mut A, Vec<Asset>) + 'static>(
+        &mut self,
+        relative_paths: &[impl AsRef<str>],
+        callback: C,
+    ) -> RequestHandle {
+        self.read_with_priority(relative_paths, 0, callback)
+    }
+
+    /// Requests a read of a set of assets with the given priority. Reads from higher priority
+    /// requests are started before reads from lower priority requests. Requests made with `read`
+    /// have a priority of 0.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** The path is relative to the current working directory.
+    /// - **Web:** The path is relative to the current url's root. Priority is ignored.
+    pub fn read_with_priority<C: FnMut(&mut Context<A>, &mut A, Vec<Asset>) + 'static>(
+        &mut self,
+        relative_paths: &[impl AsRef<str>],
+        priority: i32,
+        callback: C,
+    ) -> RequestHandle {
+        let request = AssetRequest::new(relative_paths, callback).with_priority(priority);
+        let handle = request.progress.clone();
+        self.assets().read(request);
+        handle
+    }
+
+    /// Requests a read of a set of assets, decoding each into `T`. The callback is called with the
+    /// decoded assets once all assets requested have completed. Decoding failures are reported as
+    /// `LoaderError::InvalidData`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** The path is relative to the current working directory. Decoding happens on
+    ///   the asset thread.
+    /// - **Web:** The path is relative to the current url's root. Decoding happens on the main
+    ///   thread.
+    pub fn load<T: Loadable, C: FnMut(&mut Context<A>, &mut A, Vec<Asset<T>>) + 'static>(
         &mut self,
         relative_paths: &[impl AsRef<str>],
         callback: C,
-    ) {
-        let request = AssetRequest::new(relative_paths, callback);
+    ) -> RequestHandle {
+        self.load_with_priority(relative_paths, 0, callback)
+    }
+
+    /// Requests a read of a set of assets with the given priority, decoding each into `T`. Reads
+    /// from higher priority requests are started before reads from lower priority requests.
+    /// Requests made with `load` have a priority of 0.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** The path is relative to the current working directory. Decoding happens on
+    ///   the asset thread.
+    /// - **Web:** The path is relative to the current url's root. Decoding happens on the main
+    ///   thread. Priority is ignored.
+    pub fn load_with_priority<T: Loadable, C: FnMut(&mut Context<A>, &mut A, Vec<Asset<T>>) + 'static>(
+        &mut self,
+        relative_paths: &[impl AsRef<str>],
+        priority: i32,
+        callback: C,
+    ) -> RequestHandle {
+        let request = AssetRequest::typed(relative_paths, callback).with_priority(priority);
+        let handle = request.progress.clone();
         self.assets().read(request);
+        handle
+    }
+
+    /// Watches every path successfully read from disk while watching for changes, so this should be
+    /// called before the assets to watch are read. When a file changes, it's read again, checked
+    /// against the manifest checksums, and redelivered to the given callback. This is intended for
+    /// development, so textures, shaders, and sounds can be swapped while the game is running.
+    /// Calling this again replaces the previous callback.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** Paths are polled for changes on the asset thread.
+    /// - **Web:** Unsupported. The callback is never called.
+    pub fn watch<C: FnMut(&mut Context<A>, &mut A, Asset) + 'static>(&mut self, callback: C) {
+        let callback: AssetWatchCallback<A> = Rc::new(RefCell::new(callback));
+        self.assets().watch(Some(callback));
+    }
+
+    /// Stops watching previously read paths for changes, and forgets them.
+    pub fn unwatch(&mut self) {
+        self.assets().watch(None);
+    }
+
+    /// Mounts a source into the virtual filesystem assets are read from. Paths under the mount
+    /// point are looked up in the source, with the mount point removed. Mounts are searched from
+    /// the most recently mounted to the least recently mounted, so a mods directory mounted after
+    /// the base data overlays it. By default the current working directory is mounted at the root.
+    ///
+    /// Asset paths are normalized before lookup, and paths that would escape the root with `..`
+    /// fail with `LoaderError::InvalidInput`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mount_point` - The virtual path to attach the source to. Use "" for the root.
+    /// * `source` - Where assets are read from.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Web:** Unsupported. Paths are always relative to the current url's root.
+    pub fn mount(&mut self, mount_point: &str, source: MountSource) -> Result<(), LoaderError> {
+        self.assets().mount(mount_point, source)
+    }
+
+    /// Removes every mount from the virtual filesystem, including the default working directory
+    /// mount.
+    pub fn unmount_all(&mut self) {
+        self.assets().unmount_all();
     }
 }
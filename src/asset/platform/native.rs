@@ -1,115 +1,632 @@
-use crate::asset::{Asset, AssetRequest, AssetStateContract, LoaderError};
-use crate::sync::{make as spsc_make, Consumer, Producer, Signal};
+use crate::asset::manifest::verify;
+use crate::asset::storage::{StorageOperation, StorageOutput};
+use crate::asset::vfs::{Located, Vfs};
+use crate::asset::{
+    normalize_path, Asset, AssetRequest, AssetStateContract, AssetWatchCallback, ErrorDetail, LoaderError,
+    MountSource, RequestHandle, StorageRequest,
+};
+use crate::time::Instant;
 use crate::App;
-use alloc::{sync::Arc, vec::Vec};
+use alloc::{collections::BinaryHeap, collections::VecDeque, string::String, sync::Arc, vec::Vec};
+use core::cmp::Ordering as CmpOrdering;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::time::Duration;
+use hashbrown::HashMap;
+use parking_lot::{Condvar, Mutex, RwLock};
 use std::fs::File;
-use std::{io, io::Read};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use std::{thread, thread::JoinHandle};
 
+/// How often watched paths are checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// How many bytes are read at a time. Progress and cancellation are checked between chunks.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
 pub(crate) struct AssetState<A: App> {
-    handle: JoinHandle<()>,
-    signal: Arc<Signal>,
-    pending: Vec<AssetRequest<A>>,
-    read_request_sender: Producer<AssetRequest<A>>,
-    read_result_receiver: Consumer<AssetRequest<A>>,
+    handles: Vec<JoinHandle<()>>,
+    shared: Arc<Shared<A>>,
+    sequence: u64,
+    watch_callback: Option<AssetWatchCallback<A>>,
 }
 
 impl<A: App> AssetStateContract<A> for AssetState<A> {
     fn init() -> Self {
-        let (read_request_sender, read_request_receiver): (
-            Producer<AssetRequest<A>>,
-            Consumer<AssetRequest<A>>,
-        ) = spsc_make(128);
-        let (read_result_sender, read_result_receiver): (
-            Producer<AssetRequest<A>>,
-            Consumer<AssetRequest<A>>,
-        ) = spsc_make(128);
-
-        let signal = Arc::new(Signal::new());
-        let handle_signal = signal.clone();
-
-        let handle = thread::spawn(move || loop {
-            while let Some(mut request) = read_request_receiver.try_pop() {
-                for asset in &mut request.assets {
-                    read(asset);
-                }
-                read_result_sender.push(request);
-            }
-            handle_signal.wait();
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(BinaryHeap::new()),
+            available: Condvar::new(),
+            results: Mutex::new(VecDeque::new()),
+            reloads: Mutex::new(VecDeque::new()),
+            watching: AtomicBool::new(false),
+            watcher: Mutex::new(Watcher::new()),
+            vfs: RwLock::new(Vfs::new()),
+            storage: Mutex::new(VecDeque::new()),
+            storage_root: storage_root(A::STORAGE_DIRECTORY),
+            cache: RwLock::new(HashMap::new()),
+            checksums: RwLock::new(HashMap::new()),
         });
 
+        let workers = A::ASSET_WORKERS.max(1);
+        let handles = (0..workers)
+            .map(|index| {
+                let shared = shared.clone();
+                // The first worker is responsible for polling watched paths.
+                thread::spawn(move || shared.work(index == 0))
+            })
+            .collect();
+
         AssetState {
-            handle,
-            signal,
-            pending: Vec::new(),
-            read_request_sender,
-            read_result_receiver,
+            handles,
+            shared,
+            sequence: 0,
+            watch_callback: None,
         }
     }
 
-    fn read(&mut self, request: AssetRequest<A>) {
-        if let Some(path) = self.read_request_sender.try_push(request) {
-            self.pending.push(path);
-        } else {
-            self.signal.notify();
+    fn read(&mut self, mut request: AssetRequest<A>) {
+        if request.assets.is_empty() {
+            request.decode();
+            self.shared.results.lock().push_back(request);
+            return;
+        }
+
+        let priority = request.priority;
+        let jobs = request
+            .assets
+            .iter()
+            .enumerate()
+            .map(|(index, asset)| (index, asset.relative_path.clone()))
+            .collect::<Vec<(usize, String)>>();
+        let batch = Arc::new(Batch {
+            remaining: AtomicUsize::new(jobs.len()),
+            request: Mutex::new(Some(request)),
+        });
+
+        let mut queue = self.shared.queue.lock();
+        for (index, relative_path) in jobs {
+            queue.push(Job {
+                priority,
+                sequence: self.sequence,
+                task: Task::Read {
+                    index,
+                    relative_path,
+                    batch: batch.clone(),
+                },
+            });
+            self.sequence += 1;
         }
+        self.shared.available.notify_all();
+    }
+
+    fn cache(&mut self, relative_path: String, contents: Vec<u8>) {
+        self.shared.cache.write().insert(relative_path, contents);
+    }
+
+    fn evict(&mut self, relative_path: &str) {
+        self.shared.cache.write().remove(relative_path);
+    }
+
+    fn set_checksums(&mut self, checksums: HashMap<String, u32>) {
+        *self.shared.checksums.write() = checksums;
+    }
+
+    fn storage(&mut self, request: StorageRequest<A>) {
+        self.shared.storage.lock().push_back(request);
+        let mut queue = self.shared.queue.lock();
+        queue.push(Job {
+            priority: 0,
+            sequence: self.sequence,
+            task: Task::Storage,
+        });
+        self.sequence += 1;
+        self.shared.available.notify_one();
     }
 
     fn next(&mut self) -> Option<AssetRequest<A>> {
-        // Send the backlog to be read.
-        while let Some(path) = self.pending.pop() {
-            if let Some(path) = self.read_request_sender.try_push(path) {
-                self.pending.push(path);
-                break;
-            } else {
-                self.signal.notify();
+        // Process the finished requests.
+        if let Some(request) = self.shared.results.lock().pop_front() {
+            return Some(request);
+        }
+        // Process the changed assets. These are drained and dropped when no longer watching.
+        let mut reloads = self.shared.reloads.lock();
+        while let Some(asset) = reloads.pop_front() {
+            if let Some(callback) = &self.watch_callback {
+                return Some(AssetRequest::reloaded(asset, callback));
+            }
+        }
+        None
+    }
+
+    fn watch(&mut self, callback: Option<AssetWatchCallback<A>>) {
+        self.shared.watching.store(callback.is_some(), Ordering::Relaxed);
+        if callback.is_none() {
+            self.shared.watcher.lock().clear();
+        }
+        self.watch_callback = callback;
+        let _queue = self.shared.queue.lock();
+        self.shared.available.notify_all();
+    }
+
+    fn mount(&mut self, mount_point: &str, source: MountSource) -> Result<(), LoaderError> {
+        self.shared.vfs.write().mount(mount_point, source)
+    }
+
+    fn unmount_all(&mut self) {
+        self.shared.vfs.write().clear();
+    }
+}
+
+/// State shared between the main thread and the asset workers.
+struct Shared<A: App> {
+    queue: Mutex<BinaryHeap<Job<A>>>,
+    available: Condvar,
+    results: Mutex<VecDeque<AssetRequest<A>>>,
+    reloads: Mutex<VecDeque<Asset>>,
+    watching: AtomicBool,
+    watcher: Mutex<Watcher>,
+    vfs: RwLock<Vfs>,
+    /// Pending storage operations, in the order they were requested.
+    storage: Mutex<VecDeque<StorageRequest<A>>>,
+    storage_root: Option<PathBuf>,
+    /// Contents of preloaded paths, keyed by normalized path.
+    cache: RwLock<HashMap<String, Vec<u8>>>,
+    /// Expected checksums of paths, from the manifest.
+    checksums: RwLock<HashMap<String, u32>>,
+}
+
+impl<A: App> Shared<A> {
+    fn work(&self, polls: bool) {
+        let mut last_poll = Instant::now();
+        loop {
+            let job = {
+                let mut queue = self.queue.lock();
+                loop {
+                    if let Some(job) = queue.pop() {
+                        break Some(job);
+                    }
+                    if polls && self.watching.load(Ordering::Relaxed) {
+                        self.available.wait_for(&mut queue, WATCH_INTERVAL);
+                        break None;
+                    }
+                    self.available.wait(&mut queue);
+                }
+            };
+
+            match job.map(|job| job.task) {
+                Some(Task::Read {
+                    index,
+                    relative_path,
+                    batch,
+                }) => self.run(index, relative_path, batch),
+                Some(Task::Storage) => self.run_storage(),
+                None => {}
+            }
+            if polls && self.watching.load(Ordering::Relaxed) && last_poll.elapsed() >= WATCH_INTERVAL {
+                last_poll = Instant::now();
+                let checksums = self.checksums.read();
+                self.watcher.lock().poll(&checksums, |asset| self.reloads.lock().push_back(asset));
             }
         }
-        // Process the finished requests.
-        self.read_result_receiver.try_pop()
     }
+
+    fn run(&self, index: usize, relative_path: String, batch: Arc<Batch<A>>) {
+        let progress = match batch.request.lock().as_ref() {
+            Some(request) => request.progress.clone(),
+            None => return,
+        };
+        if !progress.is_cancelled() {
+            let mut asset = Asset::new_err(relative_path, LoaderError::Pending);
+            if !self.read_cached(&mut asset, &progress) {
+                let located = {
+                    let vfs = self.vfs.read();
+                    vfs.locate(&asset.relative_path)
+                        .map_err(|error| (error, vfs.detail(&asset.relative_path, error)))
+                };
+                if let Some((path, modified)) = read(located, &mut asset, Some(&progress)) {
+                    if self.watching.load(Ordering::Relaxed) {
+                        self.watcher.lock().track(&asset.relative_path, path, modified);
+                    }
+                }
+                verify(&mut asset, &self.checksums.read());
+            }
+            progress.add_files(1);
+            if let Some(request) = batch.request.lock().as_mut() {
+                request.assets[index] = asset;
+            }
+        }
+        // The last read in the batch decodes and delivers the whole request. Cancelled requests skip
+        // decoding, but are still delivered so their callbacks are dropped on the main thread.
+        if batch.remaining.fetch_sub(1, Ordering::AcqRel) == 1 {
+            if let Some(mut request) = batch.request.lock().take() {
+                if !progress.is_cancelled() {
+                    request.decode();
+                }
+                self.results.lock().push_back(request);
+            }
+        }
+    }
+
+    /// Copies the asset's contents from memory if its path was preloaded. Returns true if it was.
+    fn read_cached(&self, asset: &mut Asset, progress: &RequestHandle) -> bool {
+        let cache = self.cache.read();
+        if cache.is_empty() {
+            return false;
+        }
+        let contents = match normalize_path(&asset.relative_path).ok().and_then(|path| cache.get(&path)) {
+            Some(contents) => contents.clone(),
+            None => return false,
+        };
+        progress.add_bytes(contents.len());
+        asset.result = Ok(contents);
+        true
+    }
+
+    /// Applies the oldest pending storage operation. The queue stays locked while the operation is
+    /// applied, so operations never overlap or reorder across workers.
+    fn run_storage(&self) {
+        let mut storage = self.storage.lock();
+        if let Some(request) = storage.pop_front() {
+            let output = match &self.storage_root {
+                Some(root) => apply_storage(root, &request.operation),
+                None => request.operation.failed(LoaderError::NotFound),
+            };
+            self.results.lock().push_back(request.complete(output));
+        }
+    }
+}
+
+/// A request being read by the asset workers. Completes once every path in it has been read.
+struct Batch<A: App> {
+    remaining: AtomicUsize,
+    request: Mutex<Option<AssetRequest<A>>>,
 }
 
-/// Relative to the current working directory.
-fn read(asset: &mut Asset) {
-    let mut file = match File::open(&asset.relative_path) {
-        Ok(file) => file,
+/// A unit of work for the asset workers. Higher priority jobs are run first, then older jobs.
+struct Job<A: App> {
+    priority: i32,
+    sequence: u64,
+    task: Task<A>,
+}
+
+enum Task<A: App> {
+    /// Reads a single path from a request.
+    Read {
+        index: usize,
+        relative_path: String,
+        batch: Arc<Batch<A>>,
+    },
+    /// Applies the oldest pending storage operation.
+    Storage,
+}
+
+impl<A: App> PartialEq for Job<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl<A: App> Eq for Job<A> {}
+
+impl<A: App> PartialOrd for Job<A> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<A: App> Ord for Job<A> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority.cmp(&other.priority).then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Tracks the last modified time of every successfully read file on disk.
+struct Watcher {
+    paths: HashMap<String, (PathBuf, SystemTime)>,
+}
+
+impl Watcher {
+    fn new() -> Watcher {
+        Watcher {
+            paths: HashMap::new(),
+        }
+    }
+
+    fn track(&mut self, relative_path: &str, path: PathBuf, modified: SystemTime) {
+        self.paths.insert(String::from(relative_path), (path, modified));
+    }
+
+    fn clear(&mut self) {
+        self.paths.clear();
+    }
+
+    /// Rereads every path whose modified time changed since it was last read, passing the new
+    /// contents to `changed`. Times that went backwards count, since restoring or copying a file
+    /// can keep an older time. Reread contents are verified against the manifest checksums.
+    fn poll<F: FnMut(Asset)>(&mut self, checksums: &HashMap<String, u32>, mut changed: F) {
+        for (relative_path, (path, last)) in self.paths.iter_mut() {
+            let modified = match std::fs::metadata(&*path).and_then(|metadata| metadata.modified()) {
+                Ok(modified) => modified,
+                Err(_) => continue,
+            };
+            if modified == *last {
+                continue;
+            }
+            let mut asset = Asset::new_err(relative_path.clone(), LoaderError::Pending);
+            if let Some(modified) = read_file(path, &mut asset, None) {
+                *last = modified;
+                verify(&mut asset, checksums);
+                changed(asset);
+            }
+        }
+    }
+}
+
+/// Reads the asset from wherever the virtual filesystem located it. Returns the path and last
+/// modified time of the file if it was read successfully from disk.
+fn read(
+    located: Result<Located, (LoaderError, ErrorDetail)>,
+    asset: &mut Asset,
+    progress: Option<&RequestHandle>,
+) -> Option<(PathBuf, SystemTime)> {
+    match located {
+        Ok(Located::File(path)) => {
+            let modified = read_file(&path, asset, progress)?;
+            Some((path, modified))
+        }
+        Ok(Located::Pack {
+            path,
+            offset,
+            length,
+        }) => {
+            read_pack_entry(&path, offset, length, asset, progress);
+            None
+        }
+        Err((error, detail)) => {
+            asset.fail(error, detail);
+            None
+        }
+    }
+}
+
+/// Fails the asset with the kind and message of the I/O error.
+fn fail(asset: &mut Asset, error: io::Error, source: String) {
+    asset.fail(error.kind().into(), ErrorDetail::new(error, Some(source)));
+}
+
+/// Returns the last modified time of the file if it was read successfully and the platform reports
+/// it.
+fn read_file(path: &Path, asset: &mut Asset, progress: Option<&RequestHandle>) -> Option<SystemTime> {
+    let result = File::open(path).and_then(|file| {
+        let capacity = file.metadata().map(|metadata| metadata.len() as usize).unwrap_or(0);
+        let contents = read_stream(&file, capacity, progress)?;
+        Ok((contents, file.metadata().and_then(|metadata| metadata.modified()).ok()))
+    });
+    match result {
+        Ok((contents, modified)) => {
+            asset.result = Ok(contents);
+            modified
+        }
         Err(error) => {
-            asset.result = Err(error.kind().into());
-            return;
+            fail(asset, error, format!("file '{}'", path.display()));
+            None
+        }
+    }
+}
+
+fn read_pack_entry(
+    path: &Path,
+    offset: u64,
+    length: u64,
+    asset: &mut Asset,
+    progress: Option<&RequestHandle>,
+) {
+    let result = File::open(path).and_then(|mut file| {
+        // The pack may have changed since its table was read, so the entry is checked again.
+        let size = file.metadata()?.len();
+        if offset.checked_add(length).filter(|&end| end <= size).is_none() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "pack entry is out of bounds"));
+        }
+        file.seek(SeekFrom::Start(offset))?;
+        let contents = read_stream(file.take(length), length as usize, progress)?;
+        if contents.len() as u64 != length {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "pack entry is truncated"));
+        }
+        Ok(contents)
+    });
+    match result {
+        Ok(contents) => asset.result = Ok(contents),
+        Err(error) => {
+            fail(asset, error, format!("pack '{}' entry '{}'", path.display(), asset.relative_path));
         }
+    }
+}
+
+/// Reads the stream to the end. Bytes are reported to the progress as they're read, and the read
+/// stops early with `Interrupted` if the request is cancelled.
+fn read_stream(
+    mut reader: impl Read,
+    capacity: usize,
+    progress: Option<&RequestHandle>,
+) -> io::Result<Vec<u8>> {
+    let mut contents = Vec::with_capacity(capacity);
+    let mut chunk = [0u8; READ_CHUNK_SIZE];
+    loop {
+        if progress.is_some_and(|progress| progress.is_cancelled()) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "request was cancelled"));
+        }
+        match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(count) => {
+                contents.extend_from_slice(&chunk[..count]);
+                if let Some(progress) = progress {
+                    progress.add_bytes(count);
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(contents)
+}
+
+/// Finds the directory save data is stored in. Returns None if the platform's data directory can't
+/// be determined.
+fn storage_root(name: &str) -> Option<PathBuf> {
+    let data = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
     };
-    let mut contents = Vec::new();
-    if let Err(error) = file.read_to_end(&mut contents) {
-        asset.result = Err(error.kind().into());
-        return;
-    }
-    asset.result = Ok(contents);
-}
-
-impl From<io::ErrorKind> for LoaderError {
-    fn from(error: io::ErrorKind) -> Self {
-        match error {
-            io::ErrorKind::NotFound => LoaderError::NotFound,
-            io::ErrorKind::PermissionDenied => LoaderError::PermissionDenied,
-            io::ErrorKind::ConnectionRefused => LoaderError::ConnectionRefused,
-            io::ErrorKind::ConnectionReset => LoaderError::ConnectionReset,
-            io::ErrorKind::ConnectionAborted => LoaderError::ConnectionAborted,
-            io::ErrorKind::NotConnected => LoaderError::NotConnected,
-            io::ErrorKind::AddrInUse => LoaderError::AddrInUse,
-            io::ErrorKind::AddrNotAvailable => LoaderError::AddrNotAvailable,
-            io::ErrorKind::BrokenPipe => LoaderError::BrokenPipe,
-            io::ErrorKind::AlreadyExists => LoaderError::AlreadyExists,
-            io::ErrorKind::WouldBlock => LoaderError::WouldBlock,
-            io::ErrorKind::InvalidInput => LoaderError::InvalidInput,
-            io::ErrorKind::InvalidData => LoaderError::InvalidData,
-            io::ErrorKind::TimedOut => LoaderError::TimedOut,
-            io::ErrorKind::WriteZero => LoaderError::WriteZero,
-            io::ErrorKind::Interrupted => LoaderError::Interrupted,
-            io::ErrorKind::Unsupported => LoaderError::Unsupported,
-            io::ErrorKind::UnexpectedEof => LoaderError::UnexpectedEof,
-            io::ErrorKind::OutOfMemory => LoaderError::OutOfMemory,
-            _ => LoaderError::Other,
+    Some(data?.join(name))
+}
+
+/// Finds the temporary file blobs are written to before they replace the original. It's kept in a
+/// hidden directory next to the save directory, so it can't collide with any key.
+fn storage_temp(root: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(root.file_name().unwrap_or_default());
+    name.push(".tmp");
+    root.with_file_name(name).join(std::process::id().to_string())
+}
+
+fn apply_storage(root: &Path, operation: &StorageOperation) -> StorageOutput {
+    match operation {
+        StorageOperation::Write {
+            key,
+            contents,
+        } => {
+            let result = write_atomic(&root.join(key), &storage_temp(root), contents);
+            StorageOutput::Done(result.map_err(|error| error.kind().into()))
+        }
+        StorageOperation::Read {
+            key,
+        } => {
+            let mut asset = Asset::new_err(key.clone(), LoaderError::Pending);
+            read_file(&root.join(key), &mut asset, None);
+            StorageOutput::Contents(asset)
+        }
+        StorageOperation::List => {
+            let mut keys = Vec::new();
+            let result = match list_keys(root, "", &mut keys) {
+                Ok(()) => {
+                    keys.sort();
+                    Ok(keys)
+                }
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+                Err(error) => Err(error.kind().into()),
+            };
+            StorageOutput::Keys(result)
+        }
+        StorageOperation::Delete {
+            key,
+        } => StorageOutput::Done(std::fs::remove_file(root.join(key)).map_err(|error| error.kind().into())),
+    }
+}
+
+/// Writes the contents to the temporary file, then renames it over the destination.
+fn write_atomic(path: &Path, temp: &Path, contents: &[u8]) -> io::Result<()> {
+    for directory in [path.parent(), temp.parent()].iter().flatten() {
+        std::fs::create_dir_all(directory)?;
+    }
+    let result = File::create(temp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    match result.and_then(|_| std::fs::rename(temp, path)) {
+        Ok(()) => Ok(()),
+        Err(error) => {
+            let _ = std::fs::remove_file(temp);
+            Err(error)
+        }
+    }
+}
+
+/// Recursively collects the keys of every blob in the directory.
+fn list_keys(directory: &Path, prefix: &str, keys: &mut Vec<String>) -> io::Result<()> {
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let name = match entry.file_name().into_string() {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        let key = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if entry.file_type()?.is_dir() {
+            list_keys(&entry.path(), &key, keys)?;
+        } else {
+            keys.push(key);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, key: &str, contents: &[u8]) -> Result<(), LoaderError> {
+        let operation = StorageOperation::Write {
+            key: String::from(key),
+            contents: contents.to_vec(),
+        };
+        match apply_storage(root, &operation) {
+            StorageOutput::Done(result) => result,
+            _ => unreachable!(),
+        }
+    }
+
+    fn read(root: &Path, key: &str) -> Result<Vec<u8>, LoaderError> {
+        match apply_storage(
+            root,
+            &StorageOperation::Read {
+                key: String::from(key),
+            },
+        ) {
+            StorageOutput::Contents(asset) => asset.result,
+            _ => unreachable!(),
         }
     }
+
+    fn list(root: &Path) -> Result<Vec<String>, LoaderError> {
+        match apply_storage(root, &StorageOperation::List) {
+            StorageOutput::Keys(keys) => keys,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn storage() {
+        let root = std::env::temp_dir().join(format!("storm-storage-{}", std::process::id())).join("game");
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(list(&root), Ok(Vec::new()));
+        assert_eq!(read(&root, "foo"), Err(LoaderError::NotFound));
+
+        // Writing a key doesn't disturb a key that looks like its temporary file.
+        write(&root, "foo.tmp", b"keep").unwrap();
+        write(&root, "foo", b"first").unwrap();
+        write(&root, "foo", b"second").unwrap();
+        write(&root, "saves/slot1", b"slot").unwrap();
+        assert_eq!(read(&root, "foo"), Ok(b"second".to_vec()));
+        assert_eq!(read(&root, "foo.tmp"), Ok(b"keep".to_vec()));
+        assert_eq!(read(&root, "saves/slot1"), Ok(b"slot".to_vec()));
+        assert_eq!(list(&root).unwrap(), ["foo", "foo.tmp", "saves/slot1"]);
+
+        let delete = StorageOperation::Delete {
+            key: String::from("foo"),
+        };
+        assert!(matches!(apply_storage(&root, &delete), StorageOutput::Done(Ok(()))));
+        assert!(matches!(apply_storage(&root, &delete), StorageOutput::Done(Err(LoaderError::NotFound))));
+        assert_eq!(list(&root).unwrap(), ["foo.tmp", "saves/slot1"]);
+
+        let _ = std::fs::remove_dir_all(root.parent().unwrap());
+    }
 }
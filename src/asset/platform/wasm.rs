@@ -1,8 +1,12 @@
 use std::cell::RefCell;
 
-use crate::asset::{AssetRequest, AssetStateContract, LoaderError};
+use crate::asset::manifest::verify;
+use crate::asset::{
+    normalize_path, AssetRequest, AssetStateContract, AssetWatchCallback, ErrorDetail, LoaderError,
+    MountSource, StorageRequest,
+};
 use crate::App;
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 use hashbrown::HashMap;
 use js_sys::{Array, Uint8Array};
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -21,6 +25,12 @@ thread_local! {
 pub(crate) struct AssetState<A: App> {
     count: usize,
     pending: HashMap<usize, AssetRequest<A>>,
+    /// Requests completed without reaching Javascript.
+    completed: Vec<AssetRequest<A>>,
+    /// Contents of preloaded paths, keyed by normalized path.
+    cache: HashMap<String, Vec<u8>>,
+    /// Expected checksums of paths, from the manifest.
+    checksums: HashMap<String, u32>,
 }
 
 impl<A: App> AssetStateContract<A> for AssetState<A> {
@@ -36,10 +46,33 @@ impl<A: App> AssetStateContract<A> for AssetState<A> {
         AssetState {
             count: 0,
             pending: HashMap::with_capacity(16),
+            completed: Vec::new(),
+            cache: HashMap::new(),
+            checksums: HashMap::new(),
         }
     }
 
-    fn read(&mut self, request: AssetRequest<A>) {
+    fn read(&mut self, mut request: AssetRequest<A>) {
+        // Requests made up entirely of preloaded paths never reach the browser.
+        let cached = !request.assets.is_empty()
+            && request.assets.iter().all(|asset| {
+                normalize_path(&asset.relative_path).is_ok_and(|path| self.cache.contains_key(&path))
+            });
+        if cached {
+            for asset in &mut request.assets {
+                let contents =
+                    normalize_path(&asset.relative_path).ok().and_then(|path| self.cache.get(&path));
+                if let Some(contents) = contents {
+                    request.progress.add_bytes(contents.len());
+                    asset.result = Ok(contents.clone());
+                }
+            }
+            request.progress.add_files(request.assets.len());
+            request.decode();
+            self.completed.push(request);
+            return;
+        }
+
         let paths = Array::new();
         for asset in &request.assets {
             paths.push(&JsValue::from_str(&asset.relative_path));
@@ -50,6 +83,9 @@ impl<A: App> AssetStateContract<A> for AssetState<A> {
     }
 
     fn next(&mut self) -> Option<AssetRequest<A>> {
+        if !self.completed.is_empty() {
+            return Some(self.completed.remove(0));
+        }
         STORM_ASSET_FINISHED.with_borrow_mut(|pending| match pending.pop() {
             Some((slot_key, responses)) => {
                 let slot_request = self.pending.remove(&slot_key);
@@ -59,17 +95,28 @@ impl<A: App> AssetStateContract<A> for AssetState<A> {
                             if response.is_object() {
                                 let contents: Uint8Array = response.dyn_into().unwrap();
                                 let contents = contents.to_vec();
+                                request.progress.add_bytes(contents.len());
                                 request.assets[index].result = Ok(contents);
+                                verify(&mut request.assets[index], &self.checksums);
                             } else {
                                 let status = response.as_f64().unwrap() as u32;
-                                let status = match status {
+                                let error = match status {
                                     400 => LoaderError::InvalidInput,
                                     401 | 402 | 403 => LoaderError::PermissionDenied,
                                     404 => LoaderError::NotFound,
                                     _ => LoaderError::Other,
                                 };
-                                request.assets[index].result = Err(status);
+                                let asset = &mut request.assets[index];
+                                let detail = ErrorDetail::new(
+                                    alloc::format!("HTTP status {}", status),
+                                    Some(alloc::format!("url '{}'", asset.relative_path)),
+                                );
+                                asset.fail(error, detail);
                             }
+                            request.progress.add_files(1);
+                        }
+                        if !request.progress.is_cancelled() {
+                            request.decode();
                         }
                         return Some(request);
                     }
@@ -82,4 +129,29 @@ impl<A: App> AssetStateContract<A> for AssetState<A> {
             _ => None,
         })
     }
+
+    fn watch(&mut self, _callback: Option<AssetWatchCallback<A>>) {}
+
+    fn mount(&mut self, _mount_point: &str, _source: MountSource) -> Result<(), LoaderError> {
+        Err(LoaderError::Unsupported)
+    }
+
+    fn unmount_all(&mut self) {}
+
+    fn cache(&mut self, relative_path: String, contents: Vec<u8>) {
+        self.cache.insert(relative_path, contents);
+    }
+
+    fn evict(&mut self, relative_path: &str) {
+        self.cache.remove(relative_path);
+    }
+
+    fn set_checksums(&mut self, checksums: HashMap<String, u32>) {
+        self.checksums = checksums;
+    }
+
+    fn storage(&mut self, request: StorageRequest<A>) {
+        let output = request.operation.failed(LoaderError::Unsupported);
+        self.completed.push(request.complete(output));
+    }
 }
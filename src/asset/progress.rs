@@ -0,0 +1,82 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+struct Progress {
+    files_total: usize,
+    files_completed: AtomicUsize,
+    bytes_read: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+/// Handle to a pending read request. This can be used to report loading progress, or to cancel the
+/// request if its assets are no longer needed.
+///
+/// ## Platform-specific
+///
+/// - **Web:** Progress is reported all at once when the whole request finishes. Cancelling doesn't
+///   stop reads already sent to the browser, but the callback is still never called.
+#[derive(Clone)]
+pub struct RequestHandle {
+    inner: Arc<Progress>,
+}
+
+impl RequestHandle {
+    pub(crate) fn new(files_total: usize) -> RequestHandle {
+        RequestHandle {
+            inner: Arc::new(Progress {
+                files_total,
+                files_completed: AtomicUsize::new(0),
+                bytes_read: AtomicUsize::new(0),
+                cancelled: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// The number of files in the request.
+    pub fn files_total(&self) -> usize {
+        self.inner.files_total
+    }
+
+    /// The number of files that have finished reading, successfully or not.
+    pub fn files_completed(&self) -> usize {
+        self.inner.files_completed.load(Ordering::Relaxed)
+    }
+
+    /// The number of bytes read so far across all files in the request.
+    pub fn bytes_read(&self) -> usize {
+        self.inner.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// The fraction of files that have finished reading, between `[0, 1]`.
+    pub fn progress(&self) -> f32 {
+        if self.inner.files_total == 0 {
+            1.0
+        } else {
+            self.files_completed() as f32 / self.inner.files_total as f32
+        }
+    }
+
+    /// Returns true if every file in the request has finished reading.
+    pub fn is_complete(&self) -> bool {
+        self.files_completed() >= self.inner.files_total
+    }
+
+    /// Cancels the request. Reads that haven't started are skipped, and the request's callback is
+    /// never called.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the request was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add_bytes(&self, bytes: usize) {
+        self.inner.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_files(&self, files: usize) {
+        self.inner.files_completed.fetch_add(files, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,329 @@
+use super::{normalize_path, Asset, AssetStateContract, Loadable, LoaderError, Manifest, RequestHandle};
+use crate::{App, Context};
+use alloc::{
+    boxed::Box,
+    rc::{Rc, Weak},
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::any::{Any, TypeId};
+use core::cell::{Ref, RefCell};
+use hashbrown::HashMap;
+
+/// The loading state of the asset behind a handle.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum AssetStatus {
+    /// The asset is being read or decoded.
+    Loading,
+    /// The asset loaded and can be accessed.
+    Ready,
+    /// The asset failed to load.
+    Failed(LoaderError),
+    /// The asset was explicitly unloaded.
+    Unloaded,
+}
+
+enum Slot<T> {
+    Loading,
+    Ready(T),
+    Failed(LoaderError),
+    Unloaded,
+}
+
+struct HandleInner<T> {
+    relative_path: String,
+    slot: RefCell<Slot<T>>,
+}
+
+/// Shared handle to an asset loaded through the asset server. Handles to the same path and type
+/// share the same underlying asset, which is kept alive as long as any handle to it exists.
+pub struct Handle<T> {
+    inner: Rc<HandleInner<T>>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Handle<T> {
+    /// The path used to query for this asset.
+    pub fn relative_path(&self) -> &str {
+        &self.inner.relative_path
+    }
+
+    /// Gets the current loading state of the asset.
+    pub fn status(&self) -> AssetStatus {
+        match &*self.inner.slot.borrow() {
+            Slot::Loading => AssetStatus::Loading,
+            Slot::Ready(_) => AssetStatus::Ready,
+            Slot::Failed(error) => AssetStatus::Failed(*error),
+            Slot::Unloaded => AssetStatus::Unloaded,
+        }
+    }
+
+    /// Returns true if the asset has loaded and can be accessed.
+    pub fn is_ready(&self) -> bool {
+        self.status() == AssetStatus::Ready
+    }
+
+    /// Gets a reference to the asset. Returns None if the asset isn't ready.
+    pub fn get(&self) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.inner.slot.borrow(), |slot| match slot {
+            Slot::Ready(value) => Some(value),
+            _ => None,
+        })
+        .ok()
+    }
+
+    /// Returns true if both handles refer to the same asset.
+    pub fn ptr_eq(&self, other: &Handle<T>) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn complete(&self, result: Result<T, LoaderError>) {
+        let mut slot = self.inner.slot.borrow_mut();
+        // Assets unloaded while in flight stay unloaded.
+        if let Slot::Loading = &*slot {
+            *slot = match result {
+                Ok(value) => Slot::Ready(value),
+                Err(error) => Slot::Failed(error),
+            };
+        }
+    }
+
+    fn unload(&self) {
+        *self.inner.slot.borrow_mut() = Slot::Unloaded;
+    }
+}
+
+/// A weak reference to a handle's asset, along with a way to unload it without knowing its type.
+struct Entry {
+    weak: Box<dyn Any>,
+    unload: fn(&dyn Any),
+}
+
+fn unload_weak<T: 'static>(weak: &dyn Any) {
+    if let Some(inner) = weak.downcast_ref::<Weak<HandleInner<T>>>().and_then(|weak| weak.upgrade()) {
+        Handle {
+            inner,
+        }
+        .unload();
+    }
+}
+
+/// Gets the key a path's handles are stored under. Paths are normalized so different spellings of
+/// the same path share handles, and match the normalized paths of manifest groups.
+fn handle_key(relative_path: &str) -> String {
+    normalize_path(relative_path).unwrap_or_else(|_| relative_path.to_string())
+}
+
+/// Maps paths to shared handles. Only weak references are held here, so assets are freed once
+/// every handle to them is dropped. Also tracks the manifest, and the paths of each preloaded
+/// group as they were when it was preloaded.
+pub(crate) struct AssetServer {
+    handles: HashMap<(TypeId, String), Entry>,
+    manifest: Manifest,
+    preloaded: HashMap<String, Vec<String>>,
+}
+
+impl AssetServer {
+    pub(crate) fn new() -> AssetServer {
+        AssetServer {
+            handles: HashMap::new(),
+            manifest: Manifest::default(),
+            preloaded: HashMap::new(),
+        }
+    }
+
+    /// Gets the live handle for the path, if one exists.
+    fn get<T: Loadable>(&self, relative_path: &str) -> Option<Handle<T>> {
+        let key = (TypeId::of::<T>(), handle_key(relative_path));
+        let weak = self.handles.get(&key)?.weak.downcast_ref::<Weak<HandleInner<T>>>()?;
+        weak.upgrade().map(|inner| Handle {
+            inner,
+        })
+    }
+
+    fn insert<T: Loadable>(&mut self, handle: &Handle<T>) {
+        let key = (TypeId::of::<T>(), handle_key(handle.relative_path()));
+        let entry = Entry {
+            weak: Box::new(Rc::downgrade(&handle.inner)),
+            unload: unload_weak::<T>,
+        };
+        self.handles.insert(key, entry);
+    }
+
+    fn remove<T: Loadable>(&mut self, relative_path: &str) -> Option<Handle<T>> {
+        let handle = self.get::<T>(relative_path);
+        self.handles.remove(&(TypeId::of::<T>(), handle_key(relative_path)));
+        handle
+    }
+
+    /// Unloads every handle to the normalized path, whatever its type.
+    fn remove_all(&mut self, relative_path: &str) {
+        self.handles.retain(|(_, path), entry| {
+            if path != relative_path {
+                return true;
+            }
+            (entry.unload)(&*entry.weak);
+            false
+        });
+    }
+
+    /// Returns true if the normalized path is in a preloaded group.
+    fn is_preloaded(&self, relative_path: &str) -> bool {
+        self.preloaded.values().any(|paths| paths.iter().any(|path| path == relative_path))
+    }
+
+    /// Stops tracking a preloaded group, returning its paths that aren't in another preloaded
+    /// group.
+    fn unload_group(&mut self, group: &str) -> Vec<String> {
+        let mut paths = self.preloaded.remove(group).unwrap_or_default();
+        paths.retain(|path| !self.is_preloaded(path));
+        paths
+    }
+}
+
+/// Asset server related functions.
+impl<A: App> Context<A> {
+    /// Gets a shared handle to the asset at the given path, decoded into `T`. If a handle to the
+    /// same path and type is still alive, including one that is still loading, it's returned
+    /// instead of reading the path again. Poll the handle's status to know when it's ready.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** The path is relative to the current working directory.
+    /// - **Web:** The path is relative to the current url's root.
+    pub fn load_handle<T: Loadable>(&mut self, relative_path: &str) -> Handle<T> {
+        if let Some(handle) = self.asset_server().get::<T>(relative_path) {
+            if handle.status() != AssetStatus::Unloaded {
+                return handle;
+            }
+        }
+
+        let handle = Handle {
+            inner: Rc::new(HandleInner {
+                relative_path: relative_path.to_string(),
+                slot: RefCell::new(Slot::Loading),
+            }),
+        };
+        self.asset_server().insert(&handle);
+        let weak = Rc::downgrade(&handle.inner);
+        self.load(&[relative_path], move |_ctx, _app, mut assets: Vec<Asset<T>>| {
+            if let (Some(inner), Some(asset)) = (weak.upgrade(), assets.pop()) {
+                Handle {
+                    inner,
+                }
+                .complete(asset.result);
+            }
+        });
+        handle
+    }
+
+    /// Unloads the asset at the given path, even if handles to it are still alive. Existing handles
+    /// report `AssetStatus::Unloaded`, and the next request for the path reads it again.
+    pub fn unload<T: Loadable>(&mut self, relative_path: &str) {
+        if let Some(handle) = self.asset_server().remove::<T>(relative_path) {
+            handle.unload();
+        }
+    }
+
+    /// Sets the manifest groups are preloaded from. Groups preloaded from the previous manifest stay
+    /// in memory until unloaded. Reads of any path with a checksum in the manifest are verified
+    /// against it from now on.
+    pub fn set_manifest(&mut self, manifest: Manifest) {
+        self.assets().set_checksums(manifest.checksums().clone());
+        self.asset_server().manifest = manifest;
+    }
+
+    /// Reads every path in a group of the manifest as a single request, and keeps their contents in
+    /// memory. Until the group is unloaded, reads of those paths, including decoding loads and
+    /// handles, are served from memory. The callback is called once the whole group has been read,
+    /// with the result of each path.
+    ///
+    /// Returns `LoaderError::NotFound` if the manifest has no group with the name.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Web:** Requests are only served from memory if every path in them was preloaded.
+    pub fn preload<C: FnMut(&mut Context<A>, &mut A, Vec<Asset<()>>) + 'static>(
+        &mut self,
+        group: &str,
+        mut callback: C,
+    ) -> Result<RequestHandle, LoaderError> {
+        let paths = self.asset_server().manifest.group(group).ok_or(LoaderError::NotFound)?.to_vec();
+        self.asset_server().preloaded.insert(group.to_string(), paths.clone());
+        let group = group.to_string();
+        Ok(self.read(&paths, move |ctx, app, assets| {
+            // The group may have been unloaded while it was being read.
+            let keep = ctx.asset_server().preloaded.contains_key(&group);
+            let mut results = Vec::with_capacity(assets.len());
+            for asset in assets {
+                let result = match asset.result {
+                    Ok(contents) => {
+                        if keep {
+                            ctx.assets().cache(asset.relative_path.clone(), contents);
+                        }
+                        Ok(())
+                    }
+                    Err(error) => Err(error),
+                };
+                results.push(Asset {
+                    relative_path: asset.relative_path,
+                    result,
+                    detail: asset.detail,
+                });
+            }
+            callback(ctx, app, results);
+        }))
+    }
+
+    /// Unloads a preloaded group. Contents kept in memory are dropped, except for paths also in
+    /// another preloaded group, and every handle to a path in the group is unloaded.
+    pub fn unload_group(&mut self, group: &str) {
+        for path in self.asset_server().unload_group(group) {
+            self.assets().evict(&path);
+            self.asset_server().remove_all(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(relative_path: &str) -> Handle<Vec<u8>> {
+        Handle {
+            inner: Rc::new(HandleInner {
+                relative_path: relative_path.to_string(),
+                slot: RefCell::new(Slot::Loading),
+            }),
+        }
+    }
+
+    #[test]
+    fn normalized_handles() {
+        let mut server = AssetServer::new();
+        let handle = handle("./data\\a.txt");
+        server.insert(&handle);
+        assert!(server.get::<Vec<u8>>("data/a.txt").is_some());
+        server.remove_all("data/a.txt");
+        assert_eq!(handle.status(), AssetStatus::Unloaded);
+        assert!(server.get::<Vec<u8>>("data/a.txt").is_none());
+    }
+
+    #[test]
+    fn groups_outlive_manifest() {
+        let mut server = AssetServer::new();
+        server.preloaded.insert(String::from("a"), alloc::vec![String::from("x"), String::from("shared")]);
+        server.preloaded.insert(String::from("b"), alloc::vec![String::from("shared")]);
+        server.manifest = Manifest::parse("[c]\nx\n").unwrap();
+        assert_eq!(server.unload_group("a"), ["x"]);
+        assert!(server.unload_group("a").is_empty());
+        assert_eq!(server.unload_group("b"), ["shared"]);
+    }
+}
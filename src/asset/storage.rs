@@ -0,0 +1,201 @@
+use super::{normalize_path, Asset, AssetRequest, AssetStateContract, LoaderError};
+use crate::{App, Context};
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// An operation on the save directory.
+pub(crate) enum StorageOperation {
+    Write {
+        key: String,
+        contents: Vec<u8>,
+    },
+    Read {
+        key: String,
+    },
+    List,
+    Delete {
+        key: String,
+    },
+}
+
+impl StorageOperation {
+    /// The output of this operation if it failed with the given error.
+    pub(crate) fn failed(&self, error: LoaderError) -> StorageOutput {
+        match self {
+            StorageOperation::Write {
+                ..
+            }
+            | StorageOperation::Delete {
+                ..
+            } => StorageOutput::Done(Err(error)),
+            StorageOperation::Read {
+                key,
+            } => StorageOutput::Contents(Asset::new_err(key.clone(), error)),
+            StorageOperation::List => StorageOutput::Keys(Err(error)),
+        }
+    }
+}
+
+/// The result of a storage operation.
+pub(crate) enum StorageOutput {
+    Done(Result<(), LoaderError>),
+    Contents(Asset),
+    Keys(Result<Vec<String>, LoaderError>),
+}
+
+type StorageCallback<A> = Box<dyn FnMut(&mut Context<A>, &mut A, StorageOutput) + 'static>;
+
+pub(crate) struct StorageRequest<A: App> {
+    pub operation: StorageOperation,
+    callback: StorageCallback<A>,
+}
+
+unsafe impl<A: App> Send for StorageRequest<A> {}
+
+impl<A: App> StorageRequest<A> {
+    fn new<C: FnMut(&mut Context<A>, &mut A, StorageOutput) + 'static>(
+        operation: StorageOperation,
+        callback: C,
+    ) -> StorageRequest<A> {
+        StorageRequest {
+            operation,
+            callback: Box::new(callback),
+        }
+    }
+
+    /// Converts the finished operation into a completed request, so it's delivered to the main
+    /// thread alongside reads.
+    pub(crate) fn complete(self, output: StorageOutput) -> AssetRequest<A> {
+        let mut callback = self.callback;
+        let mut output = Some(output);
+        AssetRequest::new(&[] as &[&str], move |ctx: &mut Context<A>, app: &mut A, _assets: Vec<Asset>| {
+            if let Some(output) = output.take() {
+                callback(ctx, app, output);
+            }
+        })
+    }
+}
+
+/// Storage related functions.
+impl<A: App> Context<A> {
+    /// Writes a blob to the save directory, replacing any existing blob with the same key. The write
+    /// is atomic, so a crash mid-write leaves the previous contents intact. The callback is called
+    /// with the result once the write completes.
+    ///
+    /// Keys are normalized like asset paths, and may contain `/` to group blobs into directories.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** Blobs are stored under `App::STORAGE_DIRECTORY` in the platform's data
+    ///   directory. This is `$XDG_DATA_HOME` or `~/.local/share` on Linux, `%APPDATA%` on Windows,
+    ///   and `~/Library/Application Support` on macOS.
+    /// - **Web:** Unsupported. The callback is called with `LoaderError::Unsupported`.
+    pub fn storage_write<C: FnMut(&mut Context<A>, &mut A, Result<(), LoaderError>) + 'static>(
+        &mut self,
+        key: &str,
+        contents: Vec<u8>,
+        mut callback: C,
+    ) {
+        self.storage_key(
+            key,
+            |key| StorageOperation::Write {
+                key,
+                contents,
+            },
+            move |ctx, app, output| {
+                if let StorageOutput::Done(result) = output {
+                    callback(ctx, app, result);
+                }
+            },
+        );
+    }
+
+    /// Reads a blob from the save directory. The callback is called with an asset whose path is the
+    /// key. Missing blobs fail with `LoaderError::NotFound`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** Blobs are stored under `App::STORAGE_DIRECTORY` in the platform's data
+    ///   directory.
+    /// - **Web:** Unsupported. The callback is called with `LoaderError::Unsupported`.
+    pub fn storage_read<C: FnMut(&mut Context<A>, &mut A, Asset) + 'static>(
+        &mut self,
+        key: &str,
+        mut callback: C,
+    ) {
+        self.storage_key(
+            key,
+            |key| StorageOperation::Read {
+                key,
+            },
+            move |ctx, app, output| {
+                if let StorageOutput::Contents(asset) = output {
+                    callback(ctx, app, asset);
+                }
+            },
+        );
+    }
+
+    /// Lists the keys of every blob in the save directory, sorted. An empty save directory, or one
+    /// that hasn't been written to yet, produces an empty list.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** Blobs are stored under `App::STORAGE_DIRECTORY` in the platform's data
+    ///   directory.
+    /// - **Web:** Unsupported. The callback is called with `LoaderError::Unsupported`.
+    pub fn storage_list<C: FnMut(&mut Context<A>, &mut A, Result<Vec<String>, LoaderError>) + 'static>(
+        &mut self,
+        mut callback: C,
+    ) {
+        let request = StorageRequest::new(StorageOperation::List, move |ctx, app, output| {
+            if let StorageOutput::Keys(result) = output {
+                callback(ctx, app, result);
+            }
+        });
+        self.assets().storage(request);
+    }
+
+    /// Deletes a blob from the save directory. Missing blobs fail with `LoaderError::NotFound`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Non-web:** Blobs are stored under `App::STORAGE_DIRECTORY` in the platform's data
+    ///   directory.
+    /// - **Web:** Unsupported. The callback is called with `LoaderError::Unsupported`.
+    pub fn storage_delete<C: FnMut(&mut Context<A>, &mut A, Result<(), LoaderError>) + 'static>(
+        &mut self,
+        key: &str,
+        mut callback: C,
+    ) {
+        self.storage_key(
+            key,
+            |key| StorageOperation::Delete {
+                key,
+            },
+            move |ctx, app, output| {
+                if let StorageOutput::Done(result) = output {
+                    callback(ctx, app, result);
+                }
+            },
+        );
+    }
+
+    /// Queues an operation on a single key. Invalid keys fail without reaching the asset thread.
+    fn storage_key<C: FnMut(&mut Context<A>, &mut A, StorageOutput) + 'static>(
+        &mut self,
+        key: &str,
+        operation: impl FnOnce(String) -> StorageOperation,
+        callback: C,
+    ) {
+        match normalize_path(key) {
+            Ok(key) if !key.is_empty() => {
+                self.assets().storage(StorageRequest::new(operation(key), callback));
+            }
+            _ => {
+                let operation = operation(String::from(key));
+                let output = operation.failed(LoaderError::InvalidInput);
+                self.assets().read(StorageRequest::new(operation, callback).complete(output));
+            }
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use super::{ErrorDetail, LoaderError};
+use alloc::{string::String, vec::Vec};
+use hashbrown::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+const PACK_MAGIC: &[u8; 4] = b"SPAK";
+const PACK_VERSION: u32 = 1;
+
+/// Where a mount reads its assets from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MountSource {
+    /// A directory on disk. Relative directories are relative to the current working directory.
+    Directory(PathBuf),
+    /// A directory relative to the directory containing the running executable. This is unaffected
+    /// by which directory the game was launched from.
+    ExecutableRelative(PathBuf),
+    /// A pack file on disk, created with `build_pack`.
+    Pack(PathBuf),
+}
+
+/// Normalizes a virtual path. Separators are converted to `/`, and `.` and empty components are
+/// removed. `..` components are resolved, but a path may never escape its root, so those are
+/// rejected along with drive prefixes.
+pub fn normalize_path(path: &str) -> Result<String, LoaderError> {
+    let mut components: Vec<&str> = Vec::new();
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.pop().is_none() {
+                    return Err(LoaderError::InvalidInput);
+                }
+            }
+            component if component.contains(':') => return Err(LoaderError::InvalidInput),
+            component => components.push(component),
+        }
+    }
+    Ok(components.join("/"))
+}
+
+/// Builds a pack file from a set of paths and their contents. Paths are normalized, and later
+/// entries replace earlier entries with the same path.
+///
+/// The layout is a `SPAK` magic, a little endian u32 version, and a u32 entry count. Each entry is
+/// a u16 path length, the UTF-8 path, a u64 offset, and a u64 length. The contents of every entry
+/// follow the entry table, with offsets relative to the start of the file.
+pub fn build_pack(files: &[(impl AsRef<str>, impl AsRef<[u8]>)]) -> Result<Vec<u8>, LoaderError> {
+    let mut entries: Vec<(String, &[u8])> = Vec::with_capacity(files.len());
+    for (path, contents) in files {
+        let path = normalize_path(path.as_ref())?;
+        if path.is_empty() || path.len() > u16::MAX as usize {
+            return Err(LoaderError::InvalidInput);
+        }
+        entries.retain(|(existing, _)| *existing != path);
+        entries.push((path, contents.as_ref()));
+    }
+
+    let table_size: usize = entries.iter().map(|(path, _)| 2 + path.len() + 16).sum();
+    let mut offset = (12 + table_size) as u64;
+    let mut pack = Vec::new();
+    pack.extend_from_slice(PACK_MAGIC);
+    pack.extend_from_slice(&PACK_VERSION.to_le_bytes());
+    pack.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (path, contents) in &entries {
+        pack.extend_from_slice(&(path.len() as u16).to_le_bytes());
+        pack.extend_from_slice(path.as_bytes());
+        pack.extend_from_slice(&offset.to_le_bytes());
+        pack.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+        offset += contents.len() as u64;
+    }
+    for (_, contents) in &entries {
+        pack.extend_from_slice(contents);
+    }
+    Ok(pack)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct PackEntry {
+    offset: u64,
+    length: u64,
+}
+
+/// Parses the entry table at the start of a pack file. Entries must lie within the pack size.
+fn read_pack_table(reader: &mut impl Read, size: u64) -> Result<HashMap<String, PackEntry>, LoaderError> {
+    fn bytes<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], LoaderError> {
+        let mut buffer = [0u8; N];
+        reader.read_exact(&mut buffer).map_err(|error| LoaderError::from(error.kind()))?;
+        Ok(buffer)
+    }
+
+    if &bytes::<4>(reader)? != PACK_MAGIC || u32::from_le_bytes(bytes(reader)?) != PACK_VERSION {
+        return Err(LoaderError::InvalidData);
+    }
+    // The count isn't trusted to preallocate, each entry takes at least 18 bytes of the table.
+    let count = u32::from_le_bytes(bytes(reader)?);
+    if count as u64 * 18 > size {
+        return Err(LoaderError::InvalidData);
+    }
+    let mut entries = HashMap::new();
+    for _ in 0..count {
+        let length = u16::from_le_bytes(bytes(reader)?) as usize;
+        let mut path = alloc::vec![0u8; length];
+        reader.read_exact(&mut path).map_err(|error| LoaderError::from(error.kind()))?;
+        let path = String::from_utf8(path).map_err(|_| LoaderError::InvalidData)?;
+        let entry = PackEntry {
+            offset: u64::from_le_bytes(bytes(reader)?),
+            length: u64::from_le_bytes(bytes(reader)?),
+        };
+        if entry.offset.checked_add(entry.length).filter(|&end| end <= size).is_none() {
+            return Err(LoaderError::InvalidData);
+        }
+        entries.insert(path, entry);
+    }
+    Ok(entries)
+}
+
+enum Source {
+    Directory(PathBuf),
+    Pack {
+        path: PathBuf,
+        entries: HashMap<String, PackEntry>,
+    },
+}
+
+struct Mount {
+    /// Normalized virtual path the mount is attached to. Empty for the root.
+    point: String,
+    source: Source,
+}
+
+impl Mount {
+    /// Strips the mount point from the path, if the path is under the mount point.
+    fn relative<'a>(&self, path: &'a str) -> Option<&'a str> {
+        if self.point.is_empty() {
+            return Some(path);
+        }
+        let rest = path.strip_prefix(self.point.as_str())?;
+        if rest.is_empty() {
+            Some(rest)
+        } else {
+            rest.strip_prefix('/')
+        }
+    }
+}
+
+/// Where a virtual path was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Located {
+    /// A file on disk.
+    File(PathBuf),
+    /// A range of bytes in a pack file.
+    Pack {
+        path: PathBuf,
+        offset: u64,
+        length: u64,
+    },
+}
+
+/// Virtual filesystem that resolves asset paths against an ordered list of mounts. Mounts are
+/// searched from the most recently mounted to the least recently mounted, so later mounts overlay
+/// earlier ones. By default the current working directory is mounted at the root.
+pub(crate) struct Vfs {
+    mounts: Vec<Mount>,
+}
+
+impl Vfs {
+    pub(crate) fn new() -> Vfs {
+        Vfs {
+            mounts: alloc::vec![Mount {
+                point: String::new(),
+                source: Source::Directory(PathBuf::new()),
+            }],
+        }
+    }
+
+    /// Attaches a source at the given virtual path. Pack files have their entry table read
+    /// immediately.
+    pub(crate) fn mount(&mut self, mount_point: &str, source: MountSource) -> Result<(), LoaderError> {
+        let point = normalize_path(mount_point)?;
+        let source = match source {
+            MountSource::Directory(path) => Source::Directory(path),
+            MountSource::ExecutableRelative(path) => {
+                let executable = std::env::current_exe().map_err(|error| LoaderError::from(error.kind()))?;
+                let directory = executable.parent().ok_or(LoaderError::NotFound)?;
+                Source::Directory(directory.join(path))
+            }
+            MountSource::Pack(path) => {
+                let mut file = File::open(&path).map_err(|error| LoaderError::from(error.kind()))?;
+                let size = file.metadata().map_err(|error| LoaderError::from(error.kind()))?.len();
+                let entries = read_pack_table(&mut std::io::BufReader::new(&mut file), size)?;
+                Source::Pack {
+                    path,
+                    entries,
+                }
+            }
+        };
+        self.mounts.push(Mount {
+            point,
+            source,
+        });
+        Ok(())
+    }
+
+    /// Removes every mount, including the default working directory mount.
+    pub(crate) fn clear(&mut self) {
+        self.mounts.clear();
+    }
+
+    /// Finds the highest priority mount containing the path.
+    pub(crate) fn locate(&self, relative_path: &str) -> Result<Located, LoaderError> {
+        let path = normalize_path(relative_path)?;
+        for mount in self.mounts.iter().rev() {
+            let relative = match mount.relative(&path) {
+                Some(relative) => relative,
+                None => continue,
+            };
+            match &mount.source {
+                Source::Directory(directory) => {
+                    let candidate = directory.join(relative);
+                    if candidate.is_file() {
+                        return Ok(Located::File(candidate));
+                    }
+                }
+                Source::Pack {
+                    path,
+                    entries,
+                } => {
+                    if let Some(entry) = entries.get(relative) {
+                        return Ok(Located::Pack {
+                            path: path.clone(),
+                            offset: entry.offset,
+                            length: entry.length,
+                        });
+                    }
+                }
+            }
+        }
+        Err(LoaderError::NotFound)
+    }
+
+    /// Describes why the path couldn't be located, including every mount that was searched.
+    pub(crate) fn detail(&self, relative_path: &str, error: LoaderError) -> ErrorDetail {
+        let path = match normalize_path(relative_path) {
+            Ok(path) => path,
+            Err(_) => return ErrorDetail::new("Path escapes the root or has a drive prefix", None),
+        };
+        let tried = self
+            .mounts
+            .iter()
+            .rev()
+            .filter(|mount| mount.relative(&path).is_some())
+            .map(|mount| match &mount.source {
+                Source::Directory(directory) => alloc::format!("directory '{}'", directory.display()),
+                Source::Pack {
+                    path,
+                    ..
+                } => alloc::format!("pack '{}'", path.display()),
+            })
+            .collect::<Vec<String>>();
+        let message = match error {
+            LoaderError::NotFound => "Not found in any mount",
+            _ => "Failed to locate",
+        };
+        let source = if tried.is_empty() {
+            String::from("no mounts")
+        } else {
+            tried.join(", ")
+        };
+        ErrorDetail::new(message, Some(source))
+    }
+}
+
+impl Default for Vfs {
+    fn default() -> Vfs {
+        Vfs::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_path("./docs/load.png").unwrap(), "docs/load.png");
+        assert_eq!(normalize_path("a\\b//c/./d").unwrap(), "a/b/c/d");
+        assert_eq!(normalize_path("a/b/../c").unwrap(), "a/c");
+        assert_eq!(normalize_path("/a").unwrap(), "a");
+        assert_eq!(normalize_path("a/../../b"), Err(LoaderError::InvalidInput));
+        assert_eq!(normalize_path(".."), Err(LoaderError::InvalidInput));
+        assert_eq!(normalize_path("C:/windows"), Err(LoaderError::InvalidInput));
+    }
+
+    #[test]
+    fn pack_table() {
+        let pack = build_pack(&[("a.txt", &b"hello"[..]), ("./dir/b.txt", &b"world!"[..])]).unwrap();
+        let entries = read_pack_table(&mut &pack[..], pack.len() as u64).unwrap();
+        let a = entries["a.txt"];
+        let b = entries["dir/b.txt"];
+        assert_eq!(&pack[a.offset as usize..(a.offset + a.length) as usize], b"hello");
+        assert_eq!(&pack[b.offset as usize..(b.offset + b.length) as usize], b"world!");
+    }
+
+    #[test]
+    fn corrupt_pack_table() {
+        // An entry running past the end of the pack.
+        let pack = build_pack(&[("a.txt", &b"hello"[..])]).unwrap();
+        let truncated = &pack[..pack.len() - 1];
+        assert_eq!(
+            read_pack_table(&mut &truncated[..], truncated.len() as u64),
+            Err(LoaderError::InvalidData)
+        );
+
+        // A huge entry count with no table behind it.
+        let mut header = pack[..8].to_vec();
+        header.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(read_pack_table(&mut &header[..], header.len() as u64), Err(LoaderError::InvalidData));
+
+        // An offset and length that overflow.
+        let mut pack = build_pack(&[("a", &b""[..])]).unwrap();
+        pack[15..23].copy_from_slice(&u64::MAX.to_le_bytes());
+        pack[23..31].copy_from_slice(&1u64.to_le_bytes());
+        assert_eq!(read_pack_table(&mut &pack[..], pack.len() as u64), Err(LoaderError::InvalidData));
+    }
+
+    #[test]
+    fn overlay_order() {
+        let mut vfs = Vfs::new();
+        vfs.clear();
+        vfs.mounts.push(Mount {
+            point: String::from("data"),
+            source: Source::Pack {
+                path: PathBuf::from("base.pak"),
+                entries: core::iter::once((
+                    String::from("a.png"),
+                    PackEntry {
+                        offset: 0,
+                        length: 1,
+                    },
+                ))
+                .collect(),
+            },
+        });
+        vfs.mounts.push(Mount {
+            point: String::from("data"),
+            source: Source::Pack {
+                path: PathBuf::from("mods.pak"),
+                entries: core::iter::once((
+                    String::from("a.png"),
+                    PackEntry {
+                        offset: 5,
+                        length: 1,
+                    },
+                ))
+                .collect(),
+            },
+        });
+        let located = vfs.locate("data/a.png").unwrap();
+        assert_eq!(
+            located,
+            Located::Pack {
+                path: PathBuf::from("mods.pak"),
+                offset: 5,
+                length: 1,
+            }
+        );
+        assert_eq!(vfs.locate("datum/a.png"), Err(LoaderError::NotFound));
+        let detail = vfs.detail("data/b.png", LoaderError::NotFound);
+        assert_eq!(detail.source.as_deref(), Some("pack 'mods.pak', pack 'base.pak'"));
+        assert_eq!(vfs.locate("data/../../a.png"), Err(LoaderError::InvalidInput));
+    }
+}
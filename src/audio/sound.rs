@@ -27,21 +27,25 @@ impl Sound {
     pub fn from_bytes(bytes: &[u8]) -> Result<Sound, SoundError> {
         let mut audio_stream = {
             let file = std::io::Cursor::new(bytes);
-            audrey::Reader::new(file).unwrap()
+            audrey::Reader::new(file).map_err(|_| SoundError::InvalidFormat)?
         };
         let description = audio_stream.description();
-        let reader = audio_stream.samples::<f32>().map(std::result::Result::unwrap);
+        let mut reader = audio_stream.samples::<f32>();
         let mut buffer = Vec::with_capacity(description.sample_rate() as usize);
         match description.channel_count() {
             1 => {
                 for x in reader {
+                    let x = x.map_err(|_| SoundError::InvalidFormat)?;
                     buffer.push([x, x]);
                 }
             }
             2 => {
-                let mut iter = reader;
-                while let Some(x) = iter.next() {
-                    let y = iter.next().unwrap() as f32;
+                while let Some(x) = reader.next() {
+                    let x = x.map_err(|_| SoundError::InvalidFormat)?;
+                    let y = match reader.next() {
+                        Some(y) => y.map_err(|_| SoundError::InvalidFormat)?,
+                        None => return Err(SoundError::InvalidFormat),
+                    };
                     buffer.push([x, y]);
                 }
             }
@@ -0,0 +1,109 @@
+//! Packs a directory of PNGs into a sprite sheet image and its metadata, to be loaded at runtime
+//! with `storm::graphics::SpriteAtlas`.
+//!
+//! ```text
+//! sprite_packer <input directory> <output image> <output metadata> [--size N] [--mip-levels N]
+//! ```
+//!
+//! Sprites are named by their path relative to the input directory, without the extension, using
+//! `/` as the separator. Without `--size`, the smallest power of two sheet the sprites fit in is
+//! used. `--mip-levels` should match the mip levels the sheet will be uploaded with, so each sprite
+//! is padded enough to avoid mip map artifacts.
+
+use std::path::Path;
+use std::process::exit;
+use storm::graphics::TextureFiltering;
+use storm::image::{Image, SpriteSheet};
+
+const MAX_SIZE: u32 = 16384;
+
+fn main() {
+    let mut positional = Vec::new();
+    let mut size = None;
+    let mut mip_levels = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => size = Some(number(args.next(), "--size")),
+            "--mip-levels" => mip_levels = Some(number(args.next(), "--mip-levels")),
+            _ => positional.push(arg),
+        }
+    }
+    if positional.len() != 3 {
+        fail("usage: sprite_packer <input directory> <output image> <output metadata> [--size N] [--mip-levels N]");
+    }
+
+    let mut sprites = Vec::new();
+    collect(Path::new(&positional[0]), "", &mut sprites);
+    if sprites.is_empty() {
+        fail(&format!("no PNGs found in '{}'", positional[0]));
+    }
+    let filtering = match mip_levels {
+        Some(mip_levels) => TextureFiltering::trilinear(mip_levels),
+        None => TextureFiltering::none(),
+    };
+
+    let sheet = match size {
+        Some(size) => SpriteSheet::pack(size, filtering, &sprites).ok(),
+        None => {
+            let mut size = 64;
+            loop {
+                if let Ok(sheet) = SpriteSheet::pack(size, filtering, &sprites) {
+                    break Some(sheet);
+                }
+                if size >= MAX_SIZE {
+                    break None;
+                }
+                size *= 2;
+            }
+        }
+    };
+    let sheet = sheet.unwrap_or_else(|| fail("the sprites don't fit in the sheet"));
+
+    let image =
+        sheet.image().to_png().unwrap_or_else(|error| fail(&format!("failed to encode: {:?}", error)));
+    write(&positional[1], image.as_slice());
+    write(&positional[2], sheet.metadata().as_bytes());
+    println!(
+        "packed {} sprites into a {}x{} sheet",
+        sheet.sprites().len(),
+        sheet.image().width(),
+        sheet.image().height()
+    );
+}
+
+/// Recursively reads every PNG under the directory, sorted by path so output is reproducible.
+fn collect(directory: &Path, prefix: &str, sprites: &mut Vec<(String, Image<storm::color::RGBA8>)>) {
+    let entries = std::fs::read_dir(directory)
+        .unwrap_or_else(|error| fail(&format!("failed to read '{}': {}", directory.display(), error)));
+    let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+    paths.sort();
+    for path in paths {
+        let stem = path.file_stem().and_then(|name| name.to_str()).unwrap_or_default();
+        if path.is_dir() {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            collect(&path, &format!("{}{}/", prefix, name), sprites);
+        } else if path.extension().and_then(|extension| extension.to_str()) == Some("png") {
+            let bytes = std::fs::read(&path)
+                .unwrap_or_else(|error| fail(&format!("failed to read '{}': {}", path.display(), error)));
+            let image = Image::from_bytes(&bytes)
+                .unwrap_or_else(|error| fail(&format!("failed to decode '{}': {:?}", path.display(), error)));
+            sprites.push((format!("{}{}", prefix, stem), image));
+        }
+    }
+}
+
+fn number(value: Option<String>, flag: &str) -> u32 {
+    value.and_then(|value| value.parse().ok()).unwrap_or_else(|| fail(&format!("{} expects a number", flag)))
+}
+
+fn write(path: &str, contents: &[u8]) {
+    if let Err(error) = std::fs::write(path, contents) {
+        fail(&format!("failed to write '{}': {}", path, error));
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("sprite_packer: {}", message);
+    exit(1);
+}
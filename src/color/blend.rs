@@ -0,0 +1,98 @@
+use super::{ColorDescriptor, R8, RG8, RGB8, RGBA8, SRGBA8};
+
+/// Draws a color over another with partial coverage, used when drawing anti-aliased shapes.
+/// Colors with alpha are composited over the destination with their alpha scaled by the coverage.
+/// `RG8` is gray and alpha. Colors without alpha are interpolated towards by the coverage.
+pub trait ColorBlend: ColorDescriptor {
+    /// Draws this color over the destination. Coverage is from 0, leaving the destination as is, to
+    /// 1, fully covering it.
+    fn blend(self, destination: Self, coverage: f32) -> Self;
+}
+
+fn lerp(destination: u8, source: u8, coverage: f32) -> u8 {
+    (destination as f32 + (source as f32 - destination as f32) * coverage).round() as u8
+}
+
+impl ColorBlend for u8 {
+    fn blend(self, destination: Self, coverage: f32) -> Self {
+        lerp(destination, self, coverage)
+    }
+}
+
+impl ColorBlend for R8 {
+    fn blend(self, destination: Self, coverage: f32) -> Self {
+        R8::new(lerp(destination.r, self.r, coverage))
+    }
+}
+
+impl ColorBlend for RG8 {
+    fn blend(self, destination: Self, coverage: f32) -> Self {
+        let gray = |color: RG8| RGBA8::new(color.r, color.r, color.r, color.g);
+        let blended = gray(self).blend(gray(destination), coverage);
+        RG8::new(blended.r, blended.a)
+    }
+}
+
+impl ColorBlend for RGB8 {
+    fn blend(self, destination: Self, coverage: f32) -> Self {
+        RGB8::new(
+            lerp(destination.r, self.r, coverage),
+            lerp(destination.g, self.g, coverage),
+            lerp(destination.b, self.b, coverage),
+        )
+    }
+}
+
+impl ColorBlend for RGBA8 {
+    fn blend(self, destination: Self, coverage: f32) -> Self {
+        let alpha = (self.a as f32 * coverage.clamp(0.0, 1.0)).round() as u8;
+        blend_over(destination, RGBA8::new(self.r, self.g, self.b, alpha))
+    }
+}
+
+/// Blends the encoded bytes like `RGBA8`, which is how `RGBA8` colors are treated in practice.
+impl ColorBlend for SRGBA8 {
+    fn blend(self, destination: Self, coverage: f32) -> Self {
+        RGBA8::from(self).blend(destination.into(), coverage).into()
+    }
+}
+
+/// Composites a straight alpha source color over a straight alpha destination color.
+pub(crate) fn blend_over(destination: RGBA8, source: RGBA8) -> RGBA8 {
+    match source.a {
+        255 => return source,
+        0 => return destination,
+        _ => {}
+    }
+    let source_alpha = source.a as u32;
+    let destination_alpha = destination.a as u32 * (255 - source_alpha) / 255;
+    let alpha = source_alpha + destination_alpha;
+    let channel = |source: u8, destination: u8| {
+        ((source as u32 * source_alpha + destination as u32 * destination_alpha + alpha / 2) / alpha) as u8
+    };
+    RGBA8::new(
+        channel(source.r, destination.r),
+        channel(source.g, destination.g),
+        channel(source.b, destination.b),
+        alpha as u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend() {
+        assert_eq!(blend_over(RGBA8::RED, RGBA8::new(0, 0, 255, 128)), RGBA8::new(127, 0, 128, 255));
+        assert_eq!(blend_over(RGBA8::RED, RGBA8::TRANSPARENT), RGBA8::RED);
+        assert_eq!(RGBA8::BLUE.blend(RGBA8::RED, 0.5), RGBA8::new(127, 0, 128, 255));
+        assert_eq!(R8::new(200).blend(R8::new(100), 0.25), R8::new(125));
+        assert_eq!(RG8::new(0, 128).blend(RG8::new(255, 255), 1.0), RG8::new(127, 255));
+        assert_eq!(RG8::new(0, 255).blend(RG8::new(255, 0), 0.5), RG8::new(0, 128));
+        assert_eq!(
+            SRGBA8::new(0, 0, 255, 255).blend(SRGBA8::new(255, 0, 0, 255), 1.0),
+            SRGBA8::new(0, 0, 255, 255)
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use super::{ColorDescriptor, R8, RG8, RGB8, RGBA8};
+
+/// A channel of an RGBA color, selected when converting to a color with fewer or different
+/// components.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+    /// The Rec. 601 luma of the red, green, and blue channels.
+    Luma,
+}
+
+impl Channel {
+    /// Reads this channel from an RGBA color.
+    pub fn read(&self, color: RGBA8) -> u8 {
+        match self {
+            Channel::Red => color.r,
+            Channel::Green => color.g,
+            Channel::Blue => color.b,
+            Channel::Alpha => color.a,
+            Channel::Luma => luma(color.r, color.g, color.b),
+        }
+    }
+}
+
+/// Converts between 8 bit color types by going through RGBA8. Expanding to RGBA copies gray to
+/// red, green, and blue, and makes colors without alpha opaque. Reducing from RGBA fills each
+/// component of the color from a selected channel.
+pub trait ColorConvert: ColorDescriptor {
+    /// The channel each component is filled from by default, in component order. Single channel
+    /// colors default to luma, and two channel colors to luma and alpha. Entries beyond the number
+    /// of components are ignored.
+    const DEFAULT_MAPPING: [Channel; 4];
+
+    /// Expands this color to RGBA.
+    fn to_rgba8(self) -> RGBA8;
+
+    /// Creates this color from an RGBA color, filling each component from the channel at the same
+    /// position in the mapping.
+    fn from_rgba8(color: RGBA8, mapping: [Channel; 4]) -> Self;
+
+    /// Converts this color to another color type using the target's default mapping.
+    fn convert<T: ColorConvert>(self) -> T {
+        T::from_rgba8(self.to_rgba8(), T::DEFAULT_MAPPING)
+    }
+
+    /// Converts this color to another color type, filling each component of the target from the
+    /// channel at the same position in the mapping.
+    fn convert_with<T: ColorConvert>(self, mapping: [Channel; 4]) -> T {
+        T::from_rgba8(self.to_rgba8(), mapping)
+    }
+}
+
+impl ColorConvert for u8 {
+    const DEFAULT_MAPPING: [Channel; 4] = [Channel::Luma, Channel::Green, Channel::Blue, Channel::Alpha];
+
+    fn to_rgba8(self) -> RGBA8 {
+        RGBA8::new(self, self, self, 255)
+    }
+
+    fn from_rgba8(color: RGBA8, mapping: [Channel; 4]) -> Self {
+        mapping[0].read(color)
+    }
+}
+
+impl ColorConvert for R8 {
+    const DEFAULT_MAPPING: [Channel; 4] = [Channel::Luma, Channel::Green, Channel::Blue, Channel::Alpha];
+
+    fn to_rgba8(self) -> RGBA8 {
+        RGBA8::new(self.r, self.r, self.r, 255)
+    }
+
+    fn from_rgba8(color: RGBA8, mapping: [Channel; 4]) -> Self {
+        R8::new(mapping[0].read(color))
+    }
+}
+
+impl ColorConvert for RG8 {
+    const DEFAULT_MAPPING: [Channel; 4] = [Channel::Luma, Channel::Alpha, Channel::Blue, Channel::Alpha];
+
+    fn to_rgba8(self) -> RGBA8 {
+        RGBA8::new(self.r, self.r, self.r, self.g)
+    }
+
+    fn from_rgba8(color: RGBA8, mapping: [Channel; 4]) -> Self {
+        RG8::new(mapping[0].read(color), mapping[1].read(color))
+    }
+}
+
+impl ColorConvert for RGB8 {
+    const DEFAULT_MAPPING: [Channel; 4] = [Channel::Red, Channel::Green, Channel::Blue, Channel::Alpha];
+
+    fn to_rgba8(self) -> RGBA8 {
+        RGBA8::new(self.r, self.g, self.b, 255)
+    }
+
+    fn from_rgba8(color: RGBA8, mapping: [Channel; 4]) -> Self {
+        RGB8::new(mapping[0].read(color), mapping[1].read(color), mapping[2].read(color))
+    }
+}
+
+impl ColorConvert for RGBA8 {
+    const DEFAULT_MAPPING: [Channel; 4] = [Channel::Red, Channel::Green, Channel::Blue, Channel::Alpha];
+
+    fn to_rgba8(self) -> RGBA8 {
+        self
+    }
+
+    fn from_rgba8(color: RGBA8, mapping: [Channel; 4]) -> Self {
+        RGBA8::new(
+            mapping[0].read(color),
+            mapping[1].read(color),
+            mapping[2].read(color),
+            mapping[3].read(color),
+        )
+    }
+}
+
+/// Rec. 601 luma, with weights summing to 256 so gray values are preserved exactly.
+pub(crate) fn luma(r: u8, g: u8, b: u8) -> u8 {
+    ((r as u32 * 77 + g as u32 * 150 + b as u32 * 29) >> 8) as u8
+}
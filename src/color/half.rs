@@ -0,0 +1,80 @@
+/// Converts a float to the bits of the nearest half precision float. Values too large for half
+/// precision become infinity.
+pub(crate) fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        let nan = if mantissa != 0 {
+            0x200
+        } else {
+            0
+        };
+        return sign | 0x7c00 | nan;
+    }
+
+    // Rounds to nearest, ties to even, by checking the bit below the kept bits, and either the
+    // lowest kept bit or any bit under the round bit.
+    let round = |value: u32, shift: u32| {
+        let half = value >> shift;
+        let round_bit = 1 << (shift - 1);
+        if value & round_bit != 0 && value & (3 * round_bit - 1) != 0 {
+            half + 1
+        } else {
+            half
+        }
+    };
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        sign | round(mantissa | 0x80_0000, (14 - exponent) as u32) as u16
+    } else {
+        // A carry out of the mantissa correctly increments the exponent.
+        sign | round(((exponent as u32) << 23) | mantissa, 13) as u16
+    }
+}
+
+/// Converts the bits of a half precision float to a float.
+pub(crate) fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits & 0x8000) as u32) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+    match exponent {
+        0 => {
+            let value = mantissa as f32 / 16_777_216.0;
+            if sign != 0 {
+                -value
+            } else {
+                value
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion() {
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.5), 0xc100);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(1.0e6), 0x7c00);
+        assert_eq!(f32_to_f16(5.960_464_5e-8), 0x0001);
+        // 1 + 2^-11 is halfway between 1 and the next half, and rounds to even.
+        assert_eq!(f32_to_f16(1.000_488_3), 0x3c00);
+        assert_eq!(f32_to_f16(1.000_488_4), 0x3c01);
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+        for bits in (0..0x7c00).step_by(7) {
+            assert_eq!(f32_to_f16(f16_to_f32(bits)), bits);
+        }
+    }
+}
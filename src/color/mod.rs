@@ -1,17 +1,48 @@
+mod blend;
+mod convert;
+mod half;
+mod r16f;
+mod r16ui;
+mod r32f;
 mod r8;
 mod rg8;
 mod rgb8;
+mod rgba16f;
+mod rgba16ui;
+mod rgba32f;
 mod rgba8;
+mod srgb;
+mod srgba8;
 
+pub use blend::ColorBlend;
+pub use convert::{Channel, ColorConvert};
+pub use r16f::R16F;
+pub use r16ui::R16UI;
+pub use r32f::R32F;
 pub use r8::R8;
 pub use rg8::RG8;
 pub use rgb8::RGB8;
+pub use rgba16f::RGBA16F;
+pub use rgba16ui::RGBA16UI;
+pub use rgba32f::RGBA32F;
 pub use rgba8::RGBA8;
+pub use srgba8::SRGBA8;
+
+pub(crate) use blend::blend_over;
+pub(crate) use convert::luma;
+pub(crate) use half::{f16_to_f32, f32_to_f16};
+pub(crate) use srgb::{linear_to_srgb, srgb_to_linear};
 
 use crate::graphics::{PixelFormat, PixelInternalFormat, PixelType};
 
 /// A trait to describe size and layout of color components.
 ///
+/// # Safety
+///
+/// Images of the color are read and written as raw bytes laid out as described, so implementors
+/// must be `#[repr(C)]` structs of only the described components, with no padding, a size of
+/// exactly `layout().components() * component_type().size()` bytes, and no invalid bit patterns.
+///
 /// # Example
 /// ```
 /// // This is an example for how to implement ColorDescriptor for a simple type.
@@ -27,7 +58,7 @@ use crate::graphics::{PixelFormat, PixelInternalFormat, PixelType};
 /// }
 ///
 /// // This allows for bytes to represent single channel resources.
-/// impl ColorDescriptor for BGRA8 {
+/// unsafe impl ColorDescriptor for BGRA8 {
 ///     fn component_type() -> ColorComponentType {
 ///         ColorComponentType::U8
 ///     }
@@ -36,12 +67,30 @@ use crate::graphics::{PixelFormat, PixelInternalFormat, PixelType};
 ///     }
 /// }
 /// ```
-pub trait ColorDescriptor: Sized + Copy + Default {
+pub unsafe trait ColorDescriptor: Sized + Copy + Default {
     /// Gets the component type of the color.
     fn component_type() -> ColorComponentType;
 
     /// Gets the layout of the color.
     fn layout() -> ColorLayoutFormat;
+
+    /// Whether the color channels are sRGB encoded, so the GPU decodes them to linear light when
+    /// sampled. Only supported for 8 bit RGBA colors.
+    fn srgb() -> bool {
+        false
+    }
+}
+
+/// Gets the internal format textures of the color type are stored in.
+pub(crate) fn gpu_format<T: ColorDescriptor>() -> PixelInternalFormat {
+    if T::srgb() {
+        assert!(
+            T::component_type() == ColorComponentType::U8 && T::layout() == ColorLayoutFormat::RGBA,
+            "sRGB colors must have 8 bit RGBA components."
+        );
+        return PixelInternalFormat::SRGB8Alpha8;
+    }
+    T::layout().gpu_format(T::component_type())
 }
 
 /// Represents the type of each color component.
@@ -49,10 +98,23 @@ pub trait ColorDescriptor: Sized + Copy + Default {
 #[repr(u32)]
 pub enum ColorComponentType {
     U8 = PixelType::UnsignedByte as u32,
+    /// Unsigned 16 bit integers, which aren't normalized when sampled.
+    U16 = PixelType::UnsignedShort as u32,
+    /// Half precision floats, stored as their bits in a `u16`.
+    F16 = PixelType::HalfFloat as u32,
     F32 = PixelType::Float as u32,
 }
 
 impl ColorComponentType {
+    /// The size of a single component in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            ColorComponentType::U8 => 1,
+            ColorComponentType::U16 | ColorComponentType::F16 => 2,
+            ColorComponentType::F32 => 4,
+        }
+    }
+
     pub(crate) fn pixel_type(&self) -> PixelType {
         unsafe { core::mem::transmute(*self) }
     }
@@ -70,22 +132,54 @@ pub enum ColorLayoutFormat {
 }
 
 impl ColorLayoutFormat {
-    pub(crate) fn gpu_format(&self) -> PixelInternalFormat {
+    /// The number of components in the layout.
+    pub fn components(&self) -> usize {
         match self {
-            ColorLayoutFormat::R => PixelInternalFormat::R8,
-            ColorLayoutFormat::RG => PixelInternalFormat::RG8,
-            ColorLayoutFormat::RGB => PixelInternalFormat::RGB8,
-            ColorLayoutFormat::RGBA => PixelInternalFormat::RGBA8,
-            ColorLayoutFormat::BGRA => PixelInternalFormat::RGBA8,
+            ColorLayoutFormat::R => 1,
+            ColorLayoutFormat::RG => 2,
+            ColorLayoutFormat::RGB => 3,
+            ColorLayoutFormat::RGBA | ColorLayoutFormat::BGRA => 4,
         }
     }
 
-    pub(crate) fn cpu_format(&self) -> PixelFormat {
-        unsafe { core::mem::transmute(*self) }
+    pub(crate) fn gpu_format(&self, component_type: ColorComponentType) -> PixelInternalFormat {
+        use ColorComponentType::*;
+        use ColorLayoutFormat::*;
+        match (component_type, self) {
+            (U8, R) => PixelInternalFormat::R8,
+            (U8, RG) => PixelInternalFormat::RG8,
+            (U8, RGB) => PixelInternalFormat::RGB8,
+            (U8, RGBA | BGRA) => PixelInternalFormat::RGBA8,
+            (U16, R) => PixelInternalFormat::R16ui,
+            (U16, RG) => PixelInternalFormat::RG16ui,
+            (U16, RGB) => PixelInternalFormat::RGB16ui,
+            (U16, RGBA | BGRA) => PixelInternalFormat::RGBA16ui,
+            (F16, R) => PixelInternalFormat::R16f,
+            (F16, RG) => PixelInternalFormat::RG16f,
+            (F16, RGB) => PixelInternalFormat::RGB16f,
+            (F16, RGBA | BGRA) => PixelInternalFormat::RGBA16f,
+            (F32, R) => PixelInternalFormat::R32f,
+            (F32, RG) => PixelInternalFormat::RG32f,
+            (F32, RGB) => PixelInternalFormat::RGB32f,
+            (F32, RGBA | BGRA) => PixelInternalFormat::RGBA32f,
+        }
+    }
+
+    pub(crate) fn cpu_format(&self, component_type: ColorComponentType) -> PixelFormat {
+        if component_type != ColorComponentType::U16 {
+            return unsafe { core::mem::transmute::<ColorLayoutFormat, PixelFormat>(*self) };
+        }
+        match self {
+            ColorLayoutFormat::R => PixelFormat::RedInteger,
+            ColorLayoutFormat::RG => PixelFormat::RGInteger,
+            ColorLayoutFormat::RGB => PixelFormat::RGBInteger,
+            ColorLayoutFormat::RGBA => PixelFormat::RGBAInteger,
+            ColorLayoutFormat::BGRA => PixelFormat::BGRAInteger,
+        }
     }
 }
 
-impl ColorDescriptor for u8 {
+unsafe impl ColorDescriptor for u8 {
     fn component_type() -> ColorComponentType {
         ColorComponentType::U8
     }
@@ -93,3 +187,31 @@ impl ColorDescriptor for u8 {
         ColorLayoutFormat::R
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texture_formats() {
+        assert_eq!(RGBA8::layout().gpu_format(RGBA8::component_type()), PixelInternalFormat::RGBA8);
+        assert_eq!(RGBA16F::layout().gpu_format(RGBA16F::component_type()), PixelInternalFormat::RGBA16f);
+        assert_eq!(R32F::layout().gpu_format(R32F::component_type()), PixelInternalFormat::R32f);
+        assert_eq!(R16UI::layout().gpu_format(R16UI::component_type()), PixelInternalFormat::R16ui);
+        assert_eq!(R16UI::layout().cpu_format(R16UI::component_type()), PixelFormat::RedInteger);
+        assert_eq!(RGBA32F::layout().cpu_format(RGBA32F::component_type()), PixelFormat::RGBA);
+        assert_eq!(f32::from(R16F::new(0.25)), 0.25);
+        assert_eq!(gpu_format::<SRGBA8>(), PixelInternalFormat::SRGB8Alpha8);
+        assert_eq!(gpu_format::<RGBA8>(), PixelInternalFormat::RGBA8);
+    }
+
+    #[test]
+    fn linear_conversions() {
+        let color = RGBA8::new(188, 0, 255, 128);
+        let linear = color.to_linear();
+        assert!((linear.r - 0.5).abs() < 0.005 && linear.g == 0.0 && (linear.b - 1.0).abs() < 1e-6);
+        assert_eq!(RGBA8::from_linear(linear), color);
+        assert_eq!(SRGBA8::from_linear(linear), SRGBA8::new(188, 0, 255, 128));
+        assert_eq!(RGB8::from_linear([2.0, 0.5, -1.0]), RGB8::new(255, 188, 0));
+    }
+}
@@ -0,0 +1,45 @@
+use super::{f16_to_f32, f32_to_f16, ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+
+/// Single channel half precision float color type, for data such as heightmaps.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct R16F {
+    /// Represents the red color channel, as the bits of a half precision float.
+    pub r: u16,
+}
+
+impl R16F {
+    /// Creates a color, rounding the channel to the nearest half precision float.
+    pub fn new(red: f32) -> R16F {
+        R16F {
+            r: f32_to_f16(red),
+        }
+    }
+}
+
+impl From<R16F> for f32 {
+    fn from(x: R16F) -> Self {
+        f16_to_f32(x.r)
+    }
+}
+
+impl From<f32> for R16F {
+    fn from(r: f32) -> Self {
+        Self::new(r)
+    }
+}
+
+impl Default for R16F {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+unsafe impl ColorDescriptor for R16F {
+    fn component_type() -> ColorComponentType {
+        ColorComponentType::F16
+    }
+    fn layout() -> ColorLayoutFormat {
+        ColorLayoutFormat::R
+    }
+}
@@ -0,0 +1,48 @@
+use super::{ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+
+/// Single channel 16 bit unsigned integer color type. Textures of this type aren't normalized, and
+/// are sampled in shaders with a `usampler2D`. They can't be filtered, so they must be created
+/// with `TextureFiltering::none()`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct R16UI {
+    /// Represents the red color channel.
+    pub r: u16,
+}
+
+impl R16UI {
+    pub const fn new(red: u16) -> R16UI {
+        R16UI {
+            r: red,
+        }
+    }
+}
+
+impl From<R16UI> for u16 {
+    fn from(x: R16UI) -> Self {
+        x.r
+    }
+}
+
+impl From<u16> for R16UI {
+    fn from(r: u16) -> Self {
+        Self::new(r)
+    }
+}
+
+impl Default for R16UI {
+    fn default() -> Self {
+        Self {
+            r: u16::MAX,
+        }
+    }
+}
+
+unsafe impl ColorDescriptor for R16UI {
+    fn component_type() -> ColorComponentType {
+        ColorComponentType::U16
+    }
+    fn layout() -> ColorLayoutFormat {
+        ColorLayoutFormat::R
+    }
+}
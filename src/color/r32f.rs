@@ -0,0 +1,46 @@
+use super::{ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+
+/// Single channel float color type, for data such as heightmaps.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct R32F {
+    /// Represents the red color channel.
+    pub r: f32,
+}
+
+impl R32F {
+    pub const fn new(red: f32) -> R32F {
+        R32F {
+            r: red,
+        }
+    }
+}
+
+impl From<R32F> for f32 {
+    fn from(x: R32F) -> Self {
+        x.r
+    }
+}
+
+impl From<f32> for R32F {
+    fn from(r: f32) -> Self {
+        Self::new(r)
+    }
+}
+
+impl Default for R32F {
+    fn default() -> Self {
+        Self {
+            r: 1.0,
+        }
+    }
+}
+
+unsafe impl ColorDescriptor for R32F {
+    fn component_type() -> ColorComponentType {
+        ColorComponentType::F32
+    }
+    fn layout() -> ColorLayoutFormat {
+        ColorLayoutFormat::R
+    }
+}
@@ -43,7 +43,7 @@ impl Default for R8 {
     }
 }
 
-impl ColorDescriptor for R8 {
+unsafe impl ColorDescriptor for R8 {
     fn component_type() -> ColorComponentType {
         ColorComponentType::U8
     }
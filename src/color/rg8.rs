@@ -66,7 +66,7 @@ impl Default for RG8 {
     }
 }
 
-impl ColorDescriptor for RG8 {
+unsafe impl ColorDescriptor for RG8 {
     fn component_type() -> ColorComponentType {
         ColorComponentType::U8
     }
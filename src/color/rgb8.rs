@@ -1,4 +1,4 @@
-use super::{ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+use super::{linear_to_srgb, srgb_to_linear, ColorComponentType, ColorDescriptor, ColorLayoutFormat};
 
 /// Simple RGB8 color type to represent colors.
 #[repr(C)]
@@ -31,6 +31,18 @@ impl RGB8 {
         }
     }
 
+    /// Decodes the sRGB encoded color channels to linear light, for lighting and blending math.
+    pub fn to_linear(self) -> [f32; 3] {
+        [srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b)]
+    }
+
+    /// Encodes linear light color channels to sRGB, reversing `to_linear`. Channels are clamped to
+    /// [0, 1].
+    pub fn from_linear(color: [f32; 3]) -> RGB8 {
+        let [r, g, b] = color;
+        RGB8::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b))
+    }
+
     /// Helper function to create this color from f32s.
     pub fn from_f32(red: f32, green: f32, blue: f32) -> RGB8 {
         RGB8 {
@@ -83,7 +95,7 @@ impl Default for RGB8 {
     }
 }
 
-impl ColorDescriptor for RGB8 {
+unsafe impl ColorDescriptor for RGB8 {
     fn component_type() -> ColorComponentType {
         ColorComponentType::U8
     }
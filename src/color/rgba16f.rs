@@ -0,0 +1,55 @@
+use super::{f16_to_f32, f32_to_f16, ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+
+/// RGBA half precision float color type, for HDR colors.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RGBA16F {
+    /// Represents the red color channel, as the bits of a half precision float.
+    pub r: u16,
+    /// Represents the green color channel, as the bits of a half precision float.
+    pub g: u16,
+    /// Represents the blue color channel, as the bits of a half precision float.
+    pub b: u16,
+    /// Represents the alpha color channel, as the bits of a half precision float.
+    pub a: u16,
+}
+
+impl RGBA16F {
+    /// Creates a color, rounding each channel to the nearest half precision float.
+    pub fn new(red: f32, green: f32, blue: f32, alpha: f32) -> RGBA16F {
+        RGBA16F {
+            r: f32_to_f16(red),
+            g: f32_to_f16(green),
+            b: f32_to_f16(blue),
+            a: f32_to_f16(alpha),
+        }
+    }
+}
+
+impl From<RGBA16F> for [f32; 4] {
+    fn from(x: RGBA16F) -> Self {
+        [f16_to_f32(x.r), f16_to_f32(x.g), f16_to_f32(x.b), f16_to_f32(x.a)]
+    }
+}
+
+impl From<[f32; 4]> for RGBA16F {
+    fn from(x: [f32; 4]) -> Self {
+        let [r, g, b, a] = x;
+        Self::new(r, g, b, a)
+    }
+}
+
+impl Default for RGBA16F {
+    fn default() -> Self {
+        Self::new(1.0, 1.0, 1.0, 1.0)
+    }
+}
+
+unsafe impl ColorDescriptor for RGBA16F {
+    fn component_type() -> ColorComponentType {
+        ColorComponentType::F16
+    }
+    fn layout() -> ColorLayoutFormat {
+        ColorLayoutFormat::RGBA
+    }
+}
@@ -0,0 +1,56 @@
+use super::{ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+
+/// RGBA 16 bit unsigned integer color type. Textures of this type aren't normalized, and are
+/// sampled in shaders with a `usampler2D`. They can't be filtered, so they must be created with
+/// `TextureFiltering::none()`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RGBA16UI {
+    /// Represents the red color channel.
+    pub r: u16,
+    /// Represents the green color channel.
+    pub g: u16,
+    /// Represents the blue color channel.
+    pub b: u16,
+    /// Represents the alpha color channel.
+    pub a: u16,
+}
+
+impl RGBA16UI {
+    pub const fn new(red: u16, green: u16, blue: u16, alpha: u16) -> RGBA16UI {
+        RGBA16UI {
+            r: red,
+            g: green,
+            b: blue,
+            a: alpha,
+        }
+    }
+}
+
+impl From<RGBA16UI> for [u16; 4] {
+    fn from(x: RGBA16UI) -> Self {
+        [x.r, x.g, x.b, x.a]
+    }
+}
+
+impl From<[u16; 4]> for RGBA16UI {
+    fn from(x: [u16; 4]) -> Self {
+        let [r, g, b, a] = x;
+        Self::new(r, g, b, a)
+    }
+}
+
+impl Default for RGBA16UI {
+    fn default() -> Self {
+        Self::new(u16::MAX, u16::MAX, u16::MAX, u16::MAX)
+    }
+}
+
+unsafe impl ColorDescriptor for RGBA16UI {
+    fn component_type() -> ColorComponentType {
+        ColorComponentType::U16
+    }
+    fn layout() -> ColorLayoutFormat {
+        ColorLayoutFormat::RGBA
+    }
+}
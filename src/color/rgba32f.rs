@@ -0,0 +1,61 @@
+use super::{ColorComponentType, ColorDescriptor, ColorLayoutFormat, RGBA8};
+
+/// RGBA float color type, for HDR colors.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RGBA32F {
+    /// Represents the red color channel.
+    pub r: f32,
+    /// Represents the green color channel.
+    pub g: f32,
+    /// Represents the blue color channel.
+    pub b: f32,
+    /// Represents the alpha color channel.
+    pub a: f32,
+}
+
+impl RGBA32F {
+    pub const fn new(red: f32, green: f32, blue: f32, alpha: f32) -> RGBA32F {
+        RGBA32F {
+            r: red,
+            g: green,
+            b: blue,
+            a: alpha,
+        }
+    }
+}
+
+impl From<RGBA32F> for [f32; 4] {
+    fn from(x: RGBA32F) -> Self {
+        [x.r, x.g, x.b, x.a]
+    }
+}
+
+impl From<[f32; 4]> for RGBA32F {
+    fn from(x: [f32; 4]) -> Self {
+        let [r, g, b, a] = x;
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<RGBA8> for RGBA32F {
+    fn from(x: RGBA8) -> Self {
+        let [r, g, b, a]: [f32; 4] = x.into();
+        Self::new(r, g, b, a)
+    }
+}
+
+impl Default for RGBA32F {
+    fn default() -> Self {
+        Self::new(1.0, 1.0, 1.0, 1.0)
+    }
+}
+
+unsafe impl ColorDescriptor for RGBA32F {
+    fn component_type() -> ColorComponentType {
+        ColorComponentType::F32
+    }
+    fn layout() -> ColorLayoutFormat {
+        ColorLayoutFormat::RGBA
+    }
+}
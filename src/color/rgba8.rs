@@ -1,4 +1,6 @@
-use super::{ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+use super::{
+    linear_to_srgb, srgb_to_linear, ColorComponentType, ColorDescriptor, ColorLayoutFormat, RGBA32F,
+};
 
 /// Simple RGBA8 color type to represent colors.
 #[repr(C)]
@@ -35,6 +37,56 @@ impl RGBA8 {
         }
     }
 
+    /// Multiplies the color channels by alpha.
+    pub const fn premultiplied(self) -> RGBA8 {
+        let a = self.a as u32;
+        RGBA8::new(
+            ((self.r as u32 * a + 127) / 255) as u8,
+            ((self.g as u32 * a + 127) / 255) as u8,
+            ((self.b as u32 * a + 127) / 255) as u8,
+            self.a,
+        )
+    }
+
+    /// Divides the color channels by alpha, reversing `premultiplied`. Fully transparent colors
+    /// become transparent black.
+    pub fn unpremultiplied(self) -> RGBA8 {
+        let a = self.a as u32;
+        if a == 0 {
+            return RGBA8::TRANSPARENT;
+        }
+        let channel = |c: u8| {
+            let c = (c as u32 * 255 + a / 2) / a;
+            if c > 255 {
+                255
+            } else {
+                c as u8
+            }
+        };
+        RGBA8::new(channel(self.r), channel(self.g), channel(self.b), self.a)
+    }
+
+    /// Decodes the sRGB encoded color channels to linear light, for lighting and blending math.
+    /// Alpha is already linear, so it's only normalized.
+    pub fn to_linear(self) -> RGBA32F {
+        RGBA32F::new(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+            self.a as f32 / 255.0,
+        )
+    }
+
+    /// Encodes a linear light color to sRGB, reversing `to_linear`. Channels are clamped to [0, 1].
+    pub fn from_linear(color: RGBA32F) -> RGBA8 {
+        RGBA8::new(
+            linear_to_srgb(color.r),
+            linear_to_srgb(color.g),
+            linear_to_srgb(color.b),
+            (color.a.clamp(0.0, 1.0) * 255.0 + 0.5) as u8,
+        )
+    }
+
     /// Helper function to create this color from f32s.
     pub fn from_f32(red: f32, green: f32, blue: f32, alpha: f32) -> RGBA8 {
         RGBA8 {
@@ -91,7 +143,7 @@ impl Default for RGBA8 {
     }
 }
 
-impl ColorDescriptor for RGBA8 {
+unsafe impl ColorDescriptor for RGBA8 {
     fn component_type() -> ColorComponentType {
         ColorComponentType::U8
     }
@@ -0,0 +1,69 @@
+use super::{ColorComponentType, ColorDescriptor, ColorLayoutFormat, RGBA32F, RGBA8};
+
+/// RGBA8 color type marked as sRGB encoded. Textures of this type are decoded to linear light by
+/// the GPU when sampled, so filtering and blending in shaders happen in linear light. Alpha isn't
+/// encoded.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SRGBA8 {
+    /// Represents the sRGB encoded red color channel.
+    pub r: u8,
+    /// Represents the sRGB encoded green color channel.
+    pub g: u8,
+    /// Represents the sRGB encoded blue color channel.
+    pub b: u8,
+    /// Represents the alpha color channel.
+    pub a: u8,
+}
+
+impl SRGBA8 {
+    pub const fn new(red: u8, green: u8, blue: u8, alpha: u8) -> SRGBA8 {
+        SRGBA8 {
+            r: red,
+            g: green,
+            b: blue,
+            a: alpha,
+        }
+    }
+
+    /// Decodes the color channels to linear light.
+    pub fn to_linear(self) -> RGBA32F {
+        RGBA8::from(self).to_linear()
+    }
+
+    /// Encodes a linear light color, reversing `to_linear`. Channels are clamped to [0, 1].
+    pub fn from_linear(color: RGBA32F) -> SRGBA8 {
+        RGBA8::from_linear(color).into()
+    }
+}
+
+/// Colors written as `RGBA8` are already sRGB encoded in practice, so the bytes carry over as is.
+impl From<RGBA8> for SRGBA8 {
+    fn from(x: RGBA8) -> Self {
+        Self::new(x.r, x.g, x.b, x.a)
+    }
+}
+
+impl From<SRGBA8> for RGBA8 {
+    fn from(x: SRGBA8) -> Self {
+        Self::new(x.r, x.g, x.b, x.a)
+    }
+}
+
+impl Default for SRGBA8 {
+    fn default() -> Self {
+        Self::new(255, 255, 255, 255)
+    }
+}
+
+unsafe impl ColorDescriptor for SRGBA8 {
+    fn component_type() -> ColorComponentType {
+        ColorComponentType::U8
+    }
+    fn layout() -> ColorLayoutFormat {
+        ColorLayoutFormat::RGBA
+    }
+    fn srgb() -> bool {
+        true
+    }
+}
@@ -1,4 +1,4 @@
-use crate::asset::{AssetState, AssetStateContract};
+use crate::asset::{AssetServer, AssetState, AssetStateContract, Executor};
 use crate::audio::AudioState;
 use crate::event::EventConverter;
 use crate::graphics::{graphics, OpenGLState, OpenGLWindowContract, WindowSettings};
@@ -17,6 +17,8 @@ static mut INITIALIZED: AtomicBool = AtomicBool::new(false);
 pub struct Context<A: App> {
     // Global states
     assets: AssetState<A>,
+    asset_server: AssetServer,
+    executor: Executor<A>,
     // Context state
     stop: bool,
     control_flow: Option<ControlFlow>,
@@ -39,6 +41,8 @@ pub fn start<A: App>(desc: WindowSettings) -> ! {
     let assets = AssetState::init();
     let mut ctx = Context {
         assets,
+        asset_server: AssetServer::new(),
+        executor: Executor::new(),
         stop: false,
         control_flow: Some(ControlFlow::Poll),
         last_update: Instant::now(),
@@ -63,6 +67,7 @@ pub fn start<A: App>(desc: WindowSettings) -> ! {
                 while let Some(response) = ctx.assets.next() {
                     response.call(&mut ctx, &mut app);
                 }
+                ctx.poll_tasks(&mut app);
                 let now = Instant::now();
                 if now >= ctx.wait_next {
                     {
@@ -125,6 +130,14 @@ impl<A: App> Context<A> {
         &mut self.assets
     }
 
+    pub(crate) fn asset_server(&mut self) -> &mut AssetServer {
+        &mut self.asset_server
+    }
+
+    pub(crate) fn executor(&mut self) -> &mut Executor<A> {
+        &mut self.executor
+    }
+
     /// Stops the context after the next update.
     pub fn request_stop(&mut self) {
         self.stop = true;
@@ -9,6 +9,7 @@ mod frame_buffer;
 mod index_buffer;
 mod opengl;
 mod shader;
+mod sprite_atlas;
 mod state;
 mod texture;
 mod texture_atlas;
@@ -22,6 +23,7 @@ pub use self::buffer::Buffer;
 pub use self::index_buffer::IndexBuffer;
 pub use self::opengl::{BlendFactor, ClearMode, DepthTest, DrawMode, IndiceType};
 pub use self::shader::{Shader, ShaderDescription};
+pub use self::sprite_atlas::SpriteAtlas;
 pub use self::texture::{Texture, TextureFiltering};
 pub use self::texture_atlas::TextureAtlas;
 pub use self::texture_section::TextureSection;
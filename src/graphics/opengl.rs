@@ -353,6 +353,56 @@ pub enum PixelInternalFormat {
     RGBA32ui = glow::RGBA32UI,
 }
 
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CompressedInternalFormat {
+    RGBAS3tcDxt1 = glow::COMPRESSED_RGBA_S3TC_DXT1_EXT,
+    RGBAS3tcDxt3 = glow::COMPRESSED_RGBA_S3TC_DXT3_EXT,
+    RGBAS3tcDxt5 = glow::COMPRESSED_RGBA_S3TC_DXT5_EXT,
+    SRGBAlphaS3tcDxt1 = glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT,
+    SRGBAlphaS3tcDxt3 = glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT,
+    SRGBAlphaS3tcDxt5 = glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT,
+    RedRgtc1 = glow::COMPRESSED_RED_RGTC1,
+    RGRgtc2 = glow::COMPRESSED_RG_RGTC2,
+    RGBBptcUnsignedFloat = glow::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT,
+    RGBABptcUnorm = glow::COMPRESSED_RGBA_BPTC_UNORM,
+    SRGBAlphaBptcUnorm = glow::COMPRESSED_SRGB_ALPHA_BPTC_UNORM,
+    RGB8Etc2 = glow::COMPRESSED_RGB8_ETC2,
+    RGB8PunchthroughAlpha1Etc2 = glow::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+    RGBA8Etc2Eac = glow::COMPRESSED_RGBA8_ETC2_EAC,
+    SRGB8Etc2 = glow::COMPRESSED_SRGB8_ETC2,
+    SRGB8PunchthroughAlpha1Etc2 = glow::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+    SRGB8Alpha8Etc2Eac = glow::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+    RGBAAstc4x4 = 0x93B0,
+    RGBAAstc5x4 = 0x93B1,
+    RGBAAstc5x5 = 0x93B2,
+    RGBAAstc6x5 = 0x93B3,
+    RGBAAstc6x6 = 0x93B4,
+    RGBAAstc8x5 = 0x93B5,
+    RGBAAstc8x6 = 0x93B6,
+    RGBAAstc8x8 = 0x93B7,
+    RGBAAstc10x5 = 0x93B8,
+    RGBAAstc10x6 = 0x93B9,
+    RGBAAstc10x8 = 0x93BA,
+    RGBAAstc10x10 = 0x93BB,
+    RGBAAstc12x10 = 0x93BC,
+    RGBAAstc12x12 = 0x93BD,
+    SRGB8Alpha8Astc4x4 = 0x93D0,
+    SRGB8Alpha8Astc5x4 = 0x93D1,
+    SRGB8Alpha8Astc5x5 = 0x93D2,
+    SRGB8Alpha8Astc6x5 = 0x93D3,
+    SRGB8Alpha8Astc6x6 = 0x93D4,
+    SRGB8Alpha8Astc8x5 = 0x93D5,
+    SRGB8Alpha8Astc8x6 = 0x93D6,
+    SRGB8Alpha8Astc8x8 = 0x93D7,
+    SRGB8Alpha8Astc10x5 = 0x93D8,
+    SRGB8Alpha8Astc10x6 = 0x93D9,
+    SRGB8Alpha8Astc10x8 = 0x93DA,
+    SRGB8Alpha8Astc10x10 = 0x93DB,
+    SRGB8Alpha8Astc12x10 = 0x93DC,
+    SRGB8Alpha8Astc12x12 = 0x93DD,
+}
+
 #[repr(u32)]
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PixelType {
@@ -363,6 +413,7 @@ pub enum PixelType {
     UnsignedInt = glow::UNSIGNED_INT,
     Int = glow::INT,
     Float = glow::FLOAT,
+    HalfFloat = glow::HALF_FLOAT,
     UnsignedByte3_3_2 = glow::UNSIGNED_BYTE_3_3_2,
     UnsignedByte2_3_3_Rev = glow::UNSIGNED_BYTE_2_3_3_REV,
     UnsignedShort5_6_5 = glow::UNSIGNED_SHORT_5_6_5,
@@ -985,6 +1036,31 @@ impl OpenGL {
         };
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn compressed_tex_image_2d(
+        &self,
+        target: TextureLoadTarget,
+        level: i32,
+        width: i32,
+        height: i32,
+        border: i32,
+        internal_format: CompressedInternalFormat,
+        data: &[u8],
+    ) {
+        unsafe {
+            self.gl.compressed_tex_image_2d(
+                target as u32,
+                level,
+                internal_format as u32 as i32,
+                width,
+                height,
+                border,
+                data.len() as i32,
+                data,
+            )
+        };
+    }
+
     pub fn tex_sub_image_2d<T: Sized>(
         &self,
         target: TextureLoadTarget,
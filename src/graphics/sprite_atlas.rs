@@ -0,0 +1,56 @@
+use crate::graphics::{Texture, TextureFiltering, TextureSection};
+use crate::image::{Image, ImageError, SpriteSheet};
+use crate::{App, Context};
+use alloc::string::String;
+use hashbrown::HashMap;
+
+/// A sprite sheet packed ahead of time, uploaded as a texture with a texture section for each
+/// named sprite. See `image::SpriteSheet` for building sheets.
+pub struct SpriteAtlas {
+    texture: Texture,
+    sections: HashMap<String, TextureSection>,
+}
+
+impl SpriteAtlas {
+    /// Decodes a sprite sheet image and its metadata, then uploads the image to the GPU. The
+    /// filtering should match the filtering the sheet was packed with, so the padding around each
+    /// sprite is enough for its mip maps. Fails with `ImageError::InvalidData` if the metadata is
+    /// malformed or describes a sheet of a different size than the image.
+    pub fn from_bytes(
+        ctx: &Context<impl App>,
+        bytes: &[u8],
+        metadata: &str,
+        filtering: TextureFiltering,
+    ) -> Result<SpriteAtlas, ImageError> {
+        let image = Image::from_bytes(bytes)?;
+        let metadata = SpriteSheet::parse_metadata(metadata)?;
+        if image.width() != metadata.width || image.height() != metadata.height {
+            return Err(ImageError::InvalidData);
+        }
+        let texture = Texture::from_image(ctx, &image, filtering);
+        let sections = metadata
+            .sprites
+            .into_iter()
+            .map(|(name, rect)| (name, texture.subsection(rect.x, rect.x + rect.w, rect.y, rect.y + rect.h)))
+            .collect();
+        Ok(SpriteAtlas {
+            texture,
+            sections,
+        })
+    }
+
+    /// Gets the texture section of a sprite by name.
+    pub fn get(&self, name: &str) -> Option<TextureSection> {
+        self.sections.get(name).copied()
+    }
+
+    /// Gets the texture section of every sprite, by name.
+    pub fn sections(&self) -> &HashMap<String, TextureSection> {
+        &self.sections
+    }
+
+    /// Gets a reference to the underlying texture.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+}
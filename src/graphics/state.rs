@@ -4,12 +4,15 @@ use crate::graphics::{
     BlendFactor, BlendMode, Capability, ClearMode, CullFace, DepthTest, DisplayMode, OpenGL, OpenGLWindow,
     OpenGLWindowContract, PixelStoreAlignment, TextureFiltering, WindowSettings,
 };
-use crate::image::Image;
+use crate::image::{CompressedFormat, Image};
 use crate::{App, Context};
 use cgmath::*;
 use core::mem::MaybeUninit;
 use core::sync::atomic::{AtomicBool, Ordering};
 use log::trace;
+// TODO: Replace with hashbrown when glow is fixed.
+// https://github.com/grovesNL/glow/issues/205
+use std::collections::HashSet;
 
 #[no_mangle]
 static mut _STORM_GRAPHICS_INITIALIZED: AtomicBool = AtomicBool::new(false);
@@ -28,6 +31,58 @@ pub(crate) struct OpenGLState {
     default_texture: Option<Texture>,
     max_texture_size: i32,
     max_texture_anisotropy: Option<f32>,
+    compressed_formats: CompressedFormatSupport,
+}
+
+/// The families of block compressed texture formats the GPU can sample from.
+#[derive(Debug, Copy, Clone)]
+struct CompressedFormatSupport {
+    s3tc: bool,
+    s3tc_srgb: bool,
+    rgtc: bool,
+    bptc: bool,
+    etc2: bool,
+    astc: bool,
+}
+
+impl CompressedFormatSupport {
+    /// RGTC is core on desktop. WebGL exposes RGTC and BPTC, but they aren't enabled by glow.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn detect(extensions: &HashSet<String>) -> CompressedFormatSupport {
+        CompressedFormatSupport {
+            s3tc: extensions.contains("GL_EXT_texture_compression_s3tc"),
+            s3tc_srgb: extensions.contains("GL_EXT_texture_sRGB"),
+            rgtc: true,
+            bptc: extensions.contains("GL_ARB_texture_compression_bptc"),
+            etc2: extensions.contains("GL_ARB_ES3_compatibility"),
+            astc: extensions.contains("GL_KHR_texture_compression_astc_ldr"),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn detect(extensions: &HashSet<String>) -> CompressedFormatSupport {
+        CompressedFormatSupport {
+            s3tc: extensions.contains("WEBGL_compressed_texture_s3tc"),
+            s3tc_srgb: extensions.contains("WEBGL_compressed_texture_s3tc_srgb"),
+            rgtc: false,
+            bptc: false,
+            etc2: extensions.contains("WEBGL_compressed_texture_etc"),
+            astc: extensions.contains("WEBGL_compressed_texture_astc"),
+        }
+    }
+
+    /// The sRGB variants of S3TC come from a separate extension. The other families include theirs.
+    fn supports(&self, format: CompressedFormat, srgb: bool) -> bool {
+        match format {
+            CompressedFormat::Bc1 | CompressedFormat::Bc2 | CompressedFormat::Bc3 => {
+                self.s3tc && (!srgb || self.s3tc_srgb)
+            }
+            CompressedFormat::Bc4 | CompressedFormat::Bc5 => self.rgtc,
+            CompressedFormat::Bc6h | CompressedFormat::Bc7 => self.bptc,
+            CompressedFormat::Etc2Rgb | CompressedFormat::Etc2RgbA1 | CompressedFormat::Etc2Rgba => self.etc2,
+            _ => self.astc,
+        }
+    }
 }
 
 impl OpenGLState {
@@ -45,10 +100,14 @@ impl OpenGLState {
         } else {
             None
         };
+        let compressed_formats = CompressedFormatSupport::detect(extensions);
         gl.pixel_store(PixelStoreAlignment::UnpackAlignment, 1);
         gl.enable(Capability::CullFace);
         gl.enable(Capability::Blend);
         gl.enable(Capability::DepthTest);
+        if desc.srgb && window.is_srgb() {
+            gl.enable(Capability::FramebufferSrgb);
+        }
         // gl.enable(Capability::DebugOutput); // DEBUG
         // gl.debug_message_callback(|source: u32, error_type: u32, id: u32, severity: u32, message: &str| {
         //     log::warn!(
@@ -65,6 +124,7 @@ impl OpenGLState {
         gl.blend_func(BlendFactor::SourceAlpha, BlendFactor::OneMinusSourceAlpha);
         gl.cull_face(CullFace::Back);
         trace!("MAX_TEXTURE_SIZE: {}", max_texture_size);
+        trace!("Compressed texture support: {:?}", compressed_formats);
 
         unsafe {
             _STORM_GRAPHICS.write(OpenGLState {
@@ -75,6 +135,7 @@ impl OpenGLState {
                 default_texture: None,
                 max_texture_size,
                 max_texture_anisotropy,
+                compressed_formats,
             })
         };
     }
@@ -94,6 +155,12 @@ impl OpenGLState {
         self.max_texture_anisotropy
     }
 
+    /// Returns true if the GPU can sample from textures in the given compressed format, with sRGB
+    /// decoding if `srgb` is set.
+    pub(crate) fn supports_compressed_format(&self, format: CompressedFormat, srgb: bool) -> bool {
+        self.compressed_formats.supports(format, srgb)
+    }
+
     /// Resizes the viewport.
     pub(crate) fn resize(&mut self, physical: Vector2<f32>, logical: Vector2<f32>) {
         if self.logical_size != logical || self.physical_size != physical {
@@ -143,6 +210,13 @@ impl<A: App> Context<A> {
         graphics().max_texture_anisotropy
     }
 
+    /// Returns true if the GPU can sample from textures in the given compressed format. Textures in
+    /// unsupported formats are decoded on the CPU when they're created, if possible. sRGB encoded
+    /// BC1 to BC3 images also need the S3TC sRGB extension, which isn't checked here.
+    pub fn supports_compressed_format(&self, format: CompressedFormat) -> bool {
+        graphics().supports_compressed_format(format, false)
+    }
+
     /// Sets the title of the window.
     ///
     /// ## Platform-specific
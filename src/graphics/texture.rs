@@ -1,11 +1,13 @@
-use crate::color::ColorDescriptor;
+use crate::color::{gpu_format, ColorComponentType, ColorDescriptor, RGBA8, SRGBA8};
 use crate::graphics::{
-    graphics, resource, TextureBindingTarget, TextureLoadTarget, TextureMagFilterValue,
+    graphics, resource, PixelInternalFormat, TextureBindingTarget, TextureLoadTarget, TextureMagFilterValue,
     TextureMinFilterValue, TextureParameterTarget, TextureSection, TextureWrapValue,
 };
-use crate::image::Image;
+use crate::image::{CompressedImage, Image, ImageError};
 use crate::{App, Context};
 use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
 
 /// Describes how a texture will be filtered. Different settings can improve texture rendering when
 /// viewing textures far away, or at steep angles.
@@ -14,6 +16,7 @@ pub struct TextureFiltering {
     min_filter: TextureMinFilterValue,
     mip_levels: Option<i32>,
     anisotropy: Option<f32>,
+    linear_mipmaps: bool,
 }
 
 impl TextureFiltering {
@@ -23,6 +26,7 @@ impl TextureFiltering {
             min_filter: TextureMinFilterValue::Nearest,
             mip_levels: None,
             anisotropy: None,
+            linear_mipmaps: false,
         }
     }
 
@@ -36,6 +40,7 @@ impl TextureFiltering {
             min_filter: TextureMinFilterValue::LinearMipmapNearest,
             mip_levels: Some(mip_levels as i32),
             anisotropy: None,
+            linear_mipmaps: false,
         }
     }
 
@@ -49,6 +54,7 @@ impl TextureFiltering {
             min_filter: TextureMinFilterValue::LinearMipmapLinear,
             mip_levels: Some(mip_levels as i32),
             anisotropy: None,
+            linear_mipmaps: false,
         }
     }
 
@@ -68,13 +74,30 @@ impl TextureFiltering {
             min_filter: TextureMinFilterValue::LinearMipmapLinear,
             mip_levels: Some(mip_levels as i32),
             anisotropy: Some(anisotropy as f32),
+            linear_mipmaps: false,
         }
     }
 
+    /// Builds mipmaps on the CPU instead of the GPU, and uploads each level. Levels are averaged in
+    /// linear light and weighted by alpha, which keeps the edges of transparent images from
+    /// darkening. Textures are slower to create and set, and keep a copy of their full size image
+    /// in memory to rebuild the mipmaps from. This only applies to images with 8 bit components,
+    /// other images fall back to generating mipmaps on the GPU.
+    pub fn with_linear_mipmaps(mut self) -> TextureFiltering {
+        self.linear_mipmaps = true;
+        self
+    }
+
     /// Gets the requested mip levels. None if no filtering is being requested.
     pub fn mip_levels(&self) -> Option<i32> {
         self.mip_levels
     }
+
+    /// Gets the padding added around each image packed into an atlas with this filtering, to
+    /// offset mip map artifacts. None if no filtering is being requested.
+    pub(crate) fn atlas_padding(&self) -> Option<u32> {
+        self.mip_levels.map(|mip_levels| 2u32.pow(mip_levels as u32))
+    }
 }
 
 /// Represents a GPU resource for a texture.
@@ -83,6 +106,9 @@ pub struct Texture {
     filter: TextureFiltering,
     width: u32,
     height: u32,
+    compressed: bool,
+    // The full size image's bytes, kept when the mipmaps are built on the CPU.
+    base_level: Option<Rc<RefCell<Vec<u8>>>>,
     rc: Rc<()>,
 }
 
@@ -93,6 +119,8 @@ impl Clone for Texture {
             filter: self.filter,
             width: self.width,
             height: self.height,
+            compressed: self.compressed,
+            base_level: self.base_level.clone(),
             rc: self.rc.clone(),
         }
     }
@@ -105,7 +133,19 @@ impl Texture {
         Self::from_image(ctx, &Image::from_png(bytes), filtering)
     }
 
-    /// Uploads an image to the GPU, creating a texture.
+    /// Interpret a slice of bytes as an encoded image, decodes it into an RGBA image, then uploads
+    /// it to the GPU, creating a texture. See `Image::from_bytes` for the supported formats.
+    pub fn from_bytes(
+        ctx: &Context<impl App>,
+        bytes: &[u8],
+        filtering: TextureFiltering,
+    ) -> Result<Texture, ImageError> {
+        Ok(Self::from_image(ctx, &Image::from_bytes(bytes)?, filtering))
+    }
+
+    /// Uploads an image to the GPU, creating a texture. Float textures may need the
+    /// `OES_texture_float_linear` extension on WebGL to be filtered, and integer textures can't be
+    /// filtered at all, so they must be created with `TextureFiltering::none()`.
     pub fn from_image<T: ColorDescriptor>(
         ctx: &Context<impl App>,
         image: &Image<T>,
@@ -121,13 +161,24 @@ impl Texture {
                 image.height()
             );
         }
+        let unfiltered =
+            filtering.min_filter == TextureMinFilterValue::Nearest && filtering.mip_levels.is_none();
+        assert!(
+            T::component_type() != ColorComponentType::U16 || unfiltered,
+            "Integer textures must be created with TextureFiltering::none()."
+        );
         let gl = graphics().gl();
         let id = gl.create_texture();
+        let cpu_mipmaps = filtering.linear_mipmaps
+            && filtering.mip_levels.is_some()
+            && T::component_type() == ColorComponentType::U8;
         let texture = Texture {
             id,
             filter: filtering,
             width: image.width(),
             height: image.height(),
+            compressed: false,
+            base_level: cpu_mipmaps.then(|| Rc::new(RefCell::new(image.as_bytes().to_vec()))),
             rc: Rc::new(()),
         };
         let prev = gl.bind_texture(TextureBindingTarget::Texture2D, Some(id));
@@ -137,8 +188,8 @@ impl Texture {
             image.width() as i32,
             image.height() as i32,
             0,
-            T::layout().gpu_format(),
-            T::layout().cpu_format(),
+            gpu_format::<T>(),
+            T::layout().cpu_format(T::component_type()),
             T::component_type().pixel_type(),
             image.as_slice(),
         );
@@ -147,18 +198,224 @@ impl Texture {
         gl.tex_parameter_wrap_t(TextureParameterTarget::Texture2D, TextureWrapValue::ClampToEdge);
         gl.tex_parameter_min_filter(TextureParameterTarget::Texture2D, filtering.min_filter);
         gl.tex_parameter_mag_filter(TextureParameterTarget::Texture2D, TextureMagFilterValue::Nearest);
-        texture.generate_mipmap();
+        texture.generate_mipmap(image, gpu_format::<T>());
         gl.bind_texture(TextureBindingTarget::Texture2D, prev);
         texture
     }
 
-    fn generate_mipmap(&self) {
+    /// Interpret a slice of bytes as a KTX2 or DDS container, then uploads it to the GPU, creating
+    /// a texture. See `Texture::from_compressed`.
+    pub fn from_compressed_bytes(
+        ctx: &Context<impl App>,
+        bytes: &[u8],
+        filtering: TextureFiltering,
+    ) -> Result<Texture, ImageError> {
+        Self::from_compressed(ctx, &CompressedImage::from_bytes(bytes)?, filtering)
+    }
+
+    /// Uploads a compressed image to the GPU, creating a texture. The data is uploaded as is if
+    /// the GPU supports the format, otherwise it's decoded to RGBA on the CPU first. Returns
+    /// `ImageError::Unsupported` if neither is possible. Images marked as sRGB are decoded to
+    /// linear light when sampled.
+    ///
+    /// Pre-baked mip levels are used instead of generating them, up to the number of levels the
+    /// filtering requests. Mip levels can't be generated for a compressed texture without any, so
+    /// it's only sampled at full size.
+    pub fn from_compressed(
+        ctx: &Context<impl App>,
+        image: &CompressedImage,
+        filtering: TextureFiltering,
+    ) -> Result<Texture, ImageError> {
+        let max_size = ctx.max_texture_size() as u32;
+        if image.width() > max_size || image.height() > max_size {
+            return Err(ImageError::InvalidDimensions);
+        }
+        let compressed = graphics().supports_compressed_format(image.format(), image.is_srgb());
+        let decoded = if compressed {
+            Vec::new()
+        } else {
+            image.decode()?
+        };
+
+        let gl = graphics().gl();
+        let id = gl.create_texture();
+        let texture = Texture {
+            id,
+            filter: filtering,
+            width: image.width(),
+            height: image.height(),
+            compressed,
+            base_level: None,
+            rc: Rc::new(()),
+        };
+        let prev = gl.bind_texture(TextureBindingTarget::Texture2D, Some(id));
+        let decoded_format = if image.is_srgb() {
+            gpu_format::<SRGBA8>()
+        } else {
+            gpu_format::<RGBA8>()
+        };
+        let levels = match filtering.mip_levels {
+            Some(mip_levels) => image.levels().len().min(mip_levels as usize + 1),
+            None => 1,
+        };
+        for (level, data) in image.levels().iter().take(levels).enumerate() {
+            let (width, height) = image.level_dimensions(level);
+            if compressed {
+                gl.compressed_tex_image_2d(
+                    TextureLoadTarget::Texture2D,
+                    level as i32,
+                    width as i32,
+                    height as i32,
+                    0,
+                    image.format().gpu_format(image.is_srgb()),
+                    data,
+                );
+            } else {
+                gl.tex_image_2d(
+                    TextureLoadTarget::Texture2D,
+                    level as i32,
+                    width as i32,
+                    height as i32,
+                    0,
+                    decoded_format,
+                    RGBA8::layout().cpu_format(RGBA8::component_type()),
+                    RGBA8::component_type().pixel_type(),
+                    decoded[level].as_slice(),
+                );
+            }
+        }
+
+        gl.tex_parameter_wrap_s(TextureParameterTarget::Texture2D, TextureWrapValue::ClampToEdge);
+        gl.tex_parameter_wrap_t(TextureParameterTarget::Texture2D, TextureWrapValue::ClampToEdge);
+        gl.tex_parameter_min_filter(TextureParameterTarget::Texture2D, filtering.min_filter);
+        gl.tex_parameter_mag_filter(TextureParameterTarget::Texture2D, TextureMagFilterValue::Nearest);
+        if levels == 1 && !compressed {
+            texture.generate_mipmap(&decoded[0], decoded_format);
+        } else {
+            gl.tex_parameter_max_mipmaps(TextureParameterTarget::Texture2D, levels as i32 - 1);
+            texture.apply_anisotropy();
+        }
+        gl.bind_texture(TextureBindingTarget::Texture2D, prev);
+        Ok(texture)
+    }
+
+    /// Levels stop at 1x1, and every level up to the max must be defined.
+    fn max_level(&self, mip_levels: i32) -> i32 {
+        let full_chain = 31 - self.width.max(self.height).leading_zeros() as i32;
+        mip_levels.min(full_chain)
+    }
+
+    /// Generates the mipmaps of a new texture from its full size image. Levels built on the CPU
+    /// are allocated in the given internal format, which must match the full size level.
+    fn generate_mipmap<T: ColorDescriptor>(&self, image: &Image<T>, internal_format: PixelInternalFormat) {
         let gl = graphics().gl();
         if let Some(mip_levels) = self.filter.mip_levels {
-            gl.tex_parameter_max_mipmaps(TextureParameterTarget::Texture2D, mip_levels);
-            gl.generate_mipmap(TextureParameterTarget::Texture2D);
+            let chain = if self.filter.linear_mipmaps {
+                image.mipmaps(self.max_level(mip_levels) as u32).ok()
+            } else {
+                None
+            };
+            match chain {
+                Some(chain) => {
+                    gl.tex_parameter_max_mipmaps(TextureParameterTarget::Texture2D, chain.len() as i32);
+                    for (index, level) in chain.iter().enumerate() {
+                        gl.tex_image_2d(
+                            TextureLoadTarget::Texture2D,
+                            index as i32 + 1,
+                            level.width() as i32,
+                            level.height() as i32,
+                            0,
+                            internal_format,
+                            T::layout().cpu_format(T::component_type()),
+                            T::component_type().pixel_type(),
+                            level.as_slice(),
+                        );
+                    }
+                }
+                None => {
+                    gl.tex_parameter_max_mipmaps(TextureParameterTarget::Texture2D, mip_levels);
+                    gl.generate_mipmap(TextureParameterTarget::Texture2D);
+                }
+            }
+        }
+        self.apply_anisotropy();
+    }
+
+    /// Updates the mipmaps after the image was written to the texture at the given offset. When
+    /// they're built on the CPU, they're rebuilt from the kept full size image, over an aligned
+    /// region around the write.
+    fn update_mipmap<T: ColorDescriptor>(&self, offset_x: u32, offset_y: u32, image: &Image<T>) {
+        let mip_levels = match self.filter.mip_levels {
+            Some(mip_levels) => mip_levels,
+            None => return,
+        };
+        let base_level = match &self.base_level {
+            Some(base_level) => base_level,
+            None => {
+                graphics().gl().generate_mipmap(TextureParameterTarget::Texture2D);
+                return;
+            }
+        };
+        let mut base_level = base_level.borrow_mut();
+        let texel = core::mem::size_of::<T>();
+        assert_eq!(
+            base_level.len(),
+            self.width as usize * self.height as usize * texel,
+            "The image's color type doesn't match the texture."
+        );
+        let row = image.width() as usize * texel;
+        for (y, source) in image.as_bytes().chunks_exact(row).enumerate() {
+            let start = ((offset_y as usize + y) * self.width as usize + offset_x as usize) * texel;
+            base_level[start..start + row].copy_from_slice(source);
         }
 
+        let max_level = self.max_level(mip_levels) as u32;
+        let (x, y, width, height) =
+            mip_region(offset_x, offset_y, image.width(), image.height(), max_level, self.width, self.height);
+        let base = Image::<T>::from_raw_bytes(&base_level, self.width, self.height)
+            .expect("The kept image matches the texture size.");
+        let region = if (width, height) == (self.width, self.height) {
+            base
+        } else {
+            base.crop(x, y, width, height)
+        };
+        let chain = region.mipmaps(max_level).expect("Textures with CPU mipmaps have 8 bit components.");
+        for (index, level) in chain.iter().enumerate() {
+            self.set_level(index as u32 + 1, x, y, level);
+        }
+    }
+
+    /// Writes an image into a mip level, clipped to the size of the level. The offset is in full
+    /// size texels.
+    fn set_level<T: ColorDescriptor>(&self, level: u32, offset_x: u32, offset_y: u32, image: &Image<T>) {
+        let (x, y) = (offset_x >> level, offset_y >> level);
+        let (level_width, level_height) = ((self.width >> level).max(1), (self.height >> level).max(1));
+        if x >= level_width || y >= level_height {
+            return;
+        }
+        let (width, height) = (image.width().min(level_width - x), image.height().min(level_height - y));
+        let clipped;
+        let image = if (width, height) == (image.width(), image.height()) {
+            image
+        } else {
+            clipped = image.crop(0, 0, width, height);
+            &clipped
+        };
+        graphics().gl().tex_sub_image_2d(
+            TextureLoadTarget::Texture2D,
+            level as i32,
+            x as i32,
+            y as i32,
+            width as i32,
+            height as i32,
+            T::layout().cpu_format(T::component_type()),
+            T::component_type().pixel_type(),
+            image.as_slice(),
+        );
+    }
+
+    fn apply_anisotropy(&self) {
+        let gl = graphics().gl();
         if let Some(requested_anisotropy) = self.filter.anisotropy {
             if let Some(supported_anisotropy) = graphics().max_texture_anisotropy() {
                 gl.tex_parameter_anisotropy(
@@ -186,13 +443,15 @@ impl Texture {
     }
 
     /// Sets a subsection of the texture to the given image. (0, 0) is the top left of the texture,
-    /// and (width, height) is the bottom right of the texture.
+    /// and (width, height) is the bottom right of the texture. Textures uploaded in a compressed
+    /// format can't be set.
     /// # Arguments
     ///
     /// * `offset_x` - The top left texel x coordinate to offset the image by.
     /// * `offset_y` - The top left texel y coordinate to offset the image by.
     /// * `image` - The image to overwrite the texture with.
     pub fn set<Z: ColorDescriptor>(&self, offset_x: u32, offset_y: u32, image: &Image<Z>) {
+        assert!(!self.compressed, "Compressed textures can't be set.");
         assert!(image.width() + offset_x <= self.width && image.height() + offset_y <= self.height);
         let gl = graphics().gl();
         let prev = gl.bind_texture(TextureBindingTarget::Texture2D, Some(self.id));
@@ -203,11 +462,11 @@ impl Texture {
             offset_y as i32,
             image.width() as i32,
             image.height() as i32,
-            Z::layout().cpu_format(),
+            Z::layout().cpu_format(Z::component_type()),
             Z::component_type().pixel_type(),
             image.as_slice(),
         );
-        self.generate_mipmap();
+        self.update_mipmap(offset_x, offset_y, image);
         gl.bind_texture(TextureBindingTarget::Texture2D, prev);
     }
 
@@ -225,3 +484,41 @@ impl Drop for Texture {
         }
     }
 }
+
+/// Gets the region of the full size image the CPU mipmaps are rebuilt from after a write, as
+/// `(x, y, width, height)`. It's expanded to a multiple of `2^levels` texels, so every texel of
+/// each level is averaged only from texels inside it. Textures whose size isn't a multiple of that
+/// don't halve evenly, so the whole texture is used.
+fn mip_region(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    levels: u32,
+    texture_width: u32,
+    texture_height: u32,
+) -> (u32, u32, u32, u32) {
+    let align = 1u32 << levels;
+    if texture_width % align != 0 || texture_height % align != 0 {
+        return (0, 0, texture_width, texture_height);
+    }
+    let (left, top) = (x / align * align, y / align * align);
+    let right = (x + width).div_ceil(align) * align;
+    let bottom = (y + height).div_ceil(align) * align;
+    (left, top, right - left, bottom - top)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mip_regions() {
+        // A 3x3 write at an odd offset covers whole 4x4 blocks for two levels.
+        assert_eq!(mip_region(5, 3, 3, 3, 2, 16, 16), (4, 0, 4, 8));
+        assert_eq!(mip_region(8, 8, 4, 4, 2, 16, 16), (8, 8, 4, 4));
+        assert_eq!(mip_region(1, 1, 1, 1, 4, 16, 16), (0, 0, 16, 16));
+        // Sizes that don't halve evenly rebuild everything.
+        assert_eq!(mip_region(5, 3, 3, 3, 2, 18, 16), (0, 0, 18, 16));
+    }
+}
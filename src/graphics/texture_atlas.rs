@@ -1,7 +1,9 @@
 use crate::color::ColorDescriptor;
 use crate::graphics::{Texture, TextureFiltering, TextureSection};
-use crate::image::{Image, Packer};
+use crate::image::{AnimationFrame, Image, Packer};
 use crate::{App, Context};
+use alloc::vec::Vec;
+use core::time::Duration;
 
 /// Simple image atlas that adds padding to reduce mip map artifacts. Extra padding is added to
 /// packed images based on the number of mip levels. More mip levels means more space dedicated to
@@ -28,11 +30,7 @@ impl TextureAtlas {
 
         let atlas = Texture::from_image(ctx, &Image::from_color(T::default(), size, size), filtering);
         let packer = Packer::new(size, size);
-        let padding = if let Some(mip_levels) = filtering.mip_levels() {
-            Some(2u32.pow(mip_levels as u32))
-        } else {
-            None
-        };
+        let padding = filtering.atlas_padding();
 
         TextureAtlas {
             atlas,
@@ -66,6 +64,13 @@ impl TextureAtlas {
         None
     }
 
+    /// Packs every frame of an animation into the texture atlas, returning a texture section and
+    /// delay for each frame, in order. Returns None if any frame could not be fit in the atlas. Frames
+    /// packed before the failure keep their space in the atlas.
+    pub fn pack_frames(&mut self, frames: &[AnimationFrame]) -> Option<Vec<(TextureSection, Duration)>> {
+        frames.iter().map(|frame| Some((self.pack(&frame.image)?, frame.delay))).collect()
+    }
+
     /// Gets a reference to the underlying texture.
     pub fn get(&self) -> &Texture {
         &self.atlas
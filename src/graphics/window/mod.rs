@@ -22,6 +22,9 @@ use winit::event_loop::EventLoop;
 pub(crate) trait OpenGLWindowContract: Sized {
     fn new(desc: &WindowSettings, event_loop: &EventLoop<()>) -> (Self, glow::Context);
 
+    /// Returns true if the window's framebuffer can encode writes to sRGB.
+    fn is_srgb(&self) -> bool;
+
     /// Gets the scale factor of the window. This is related to DPI scaling.
     fn scale_factor(&self) -> f32;
 
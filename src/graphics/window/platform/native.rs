@@ -1,7 +1,7 @@
 use crate::graphics::{DisplayMode, OpenGLWindowContract, Vsync, WindowSettings};
 use cgmath::*;
 use glutin::ContextBuilder;
-use log::info;
+use log::{info, warn};
 use winit::dpi::LogicalSize;
 use winit::event_loop::EventLoop;
 use winit::window::{CursorGrabMode, Fullscreen, Window, WindowBuilder};
@@ -28,6 +28,9 @@ impl OpenGLWindowContract for OpenGLWindow {
             }
         }
         let mut context_builder = ContextBuilder::new();
+        if desc.srgb {
+            context_builder = context_builder.with_srgb(true);
+        }
         match desc.vsync {
             Vsync::Disabled => {
                 context_builder = context_builder.with_vsync(false);
@@ -38,6 +41,9 @@ impl OpenGLWindowContract for OpenGLWindow {
         }
         let window_context = context_builder.build_windowed(window_builder, &event_loop).unwrap();
         let window_context = unsafe { window_context.make_current() }.unwrap();
+        if desc.srgb && !window_context.get_pixel_format().srgb {
+            warn!("The window didn't get an sRGB framebuffer, ignoring.");
+        }
         let gl = unsafe {
             glow::Context::from_loader_function(|s| window_context.get_proc_address(s) as *const _)
         };
@@ -50,6 +56,10 @@ impl OpenGLWindowContract for OpenGLWindow {
         )
     }
 
+    fn is_srgb(&self) -> bool {
+        self.inner.get_pixel_format().srgb
+    }
+
     fn scale_factor(&self) -> f32 {
         self.inner.window().scale_factor() as f32
     }
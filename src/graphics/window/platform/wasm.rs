@@ -1,6 +1,6 @@
 use crate::graphics::{DisplayMode, OpenGLWindowContract, WindowSettings};
 use cgmath::*;
-use log::info;
+use log::{info, warn};
 use wasm_bindgen::JsCast;
 use winit::dpi::LogicalSize;
 use winit::event_loop::EventLoop;
@@ -45,11 +45,18 @@ impl OpenGLWindowContract for OpenGLWindow {
         };
         window.set_title(&desc.title);
         window.set_display_mode(desc.display_mode);
+        if desc.srgb {
+            warn!("sRGB framebuffers aren't supported on the web, ignoring.");
+        }
         info!("Created canvas.");
 
         (window, gl)
     }
 
+    fn is_srgb(&self) -> bool {
+        false
+    }
+
     fn scale_factor(&self) -> f32 {
         self.inner.scale_factor() as f32
     }
@@ -10,6 +10,12 @@ pub struct WindowSettings {
     pub display_mode: DisplayMode,
     /// Vsync mode for the window.
     pub vsync: Vsync,
+    /// Whether the default framebuffer is sRGB encoded. When enabled, shaders output linear light
+    /// and the GPU encodes it when writing, so blending happens in linear light. Colors passed to
+    /// shaders and clear colors are then treated as linear, see `RGBA8::to_linear`. WebGL canvases
+    /// can't be sRGB encoded, so this is ignored on the web, or if the window doesn't get an sRGB
+    /// capable framebuffer.
+    pub srgb: bool,
 }
 
 impl Default for WindowSettings {
@@ -22,6 +28,7 @@ impl Default for WindowSettings {
                 resizable: true,
             },
             vsync: Vsync::Disabled,
+            srgb: false,
         }
     }
 }
@@ -0,0 +1,80 @@
+use super::format::ImageFormat;
+use super::{Image, ImageError};
+use crate::color::RGBA8;
+use alloc::{vec, vec::Vec};
+use core::time::Duration;
+
+/// A single fully composited frame of an animation.
+#[derive(Clone)]
+pub struct AnimationFrame {
+    /// The frame, the full size of the animation.
+    pub image: Image<RGBA8>,
+    /// How long the frame is shown before the next one.
+    pub delay: Duration,
+}
+
+/// Decodes every frame of an animated GIF or PNG. Frame disposal and blending are applied, so each
+/// frame is the complete image to show. Any other supported format decodes to a single frame with no
+/// delay.
+pub fn read_frames(bytes: &[u8]) -> Result<Vec<AnimationFrame>, ImageError> {
+    match ImageFormat::sniff(bytes).ok_or(ImageError::UnknownFormat)? {
+        ImageFormat::Gif => super::gif::read_gif_frames(bytes),
+        ImageFormat::Png => super::png::read_apng(bytes),
+        _ => Ok(vec![AnimationFrame {
+            image: super::format::read_image(bytes)?,
+            delay: Duration::ZERO,
+        }]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use png::{BitDepth, BlendOp, ColorType, DisposeOp, Encoder};
+
+    #[test]
+    fn apng_disposal_and_blending() {
+        let mut bytes = Vec::new();
+        let mut encoder = Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_animated(2, 0).unwrap();
+        encoder.set_frame_delay(1, 10).unwrap();
+        encoder.set_dispose_op(DisposeOp::Background).unwrap();
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255, 0, 0, 255, 0, 255, 0, 255]).unwrap();
+        writer.set_frame_dimension(1, 1).unwrap();
+        writer.set_frame_position(1, 0).unwrap();
+        writer.set_blend_op(BlendOp::Over).unwrap();
+        writer.set_dispose_op(DisposeOp::None).unwrap();
+        writer.set_frame_delay(1, 0).unwrap();
+        writer.write_image_data(&[0, 0, 255, 128]).unwrap();
+        writer.finish().unwrap();
+
+        let frames = read_frames(&bytes).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].image.as_slice(), [RGBA8::RED, RGBA8::GREEN]);
+        assert_eq!(frames[0].delay, Duration::from_millis(100));
+        assert_eq!(frames[1].image.as_slice(), [RGBA8::TRANSPARENT, RGBA8::new(0, 0, 255, 128)]);
+        assert_eq!(frames[1].delay, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn apng_frame_count_is_untrusted() {
+        let mut bytes = Vec::new();
+        let mut encoder = Encoder::new(&mut bytes, 1, 1);
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_animated(1, 0).unwrap();
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255, 0, 0, 255]).unwrap();
+        writer.finish().unwrap();
+
+        // Claim four billion frames in the acTL chunk, fixing up its checksum.
+        let chunk = bytes.windows(4).position(|window| window == b"acTL").unwrap();
+        bytes[chunk + 4..chunk + 8].copy_from_slice(&u32::MAX.to_be_bytes());
+        let crc = crate::asset::crc32(&bytes[chunk..chunk + 12]);
+        bytes[chunk + 12..chunk + 16].copy_from_slice(&crc.to_be_bytes());
+        assert_eq!(read_frames(&bytes).err(), Some(ImageError::InvalidData));
+    }
+}
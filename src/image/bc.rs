@@ -0,0 +1,120 @@
+use crate::color::RGBA8;
+
+/// Expands a 565 color to 8 bits per channel.
+fn expand_565(color: u16) -> [u32; 3] {
+    let r = (color >> 11) as u32 & 0x1f;
+    let g = (color >> 5) as u32 & 0x3f;
+    let b = color as u32 & 0x1f;
+    [(r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2)]
+}
+
+/// Decodes the 8 byte color block shared by BC1, BC2, and BC3. Only BC1 uses the three color
+/// mode with a transparent fourth color; the others always interpolate four colors.
+fn decode_color(block: &[u8], punchthrough: bool) -> [RGBA8; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (e0, e1) = (expand_565(c0), expand_565(c1));
+    let mix = |a: u32, b: u32, d: u32| {
+        let channel = |i: usize| ((e0[i] * a + e1[i] * b) / d) as u8;
+        RGBA8::new(channel(0), channel(1), channel(2), 255)
+    };
+    let palette = if c0 > c1 || !punchthrough {
+        [mix(1, 0, 1), mix(0, 1, 1), mix(2, 1, 3), mix(1, 2, 3)]
+    } else {
+        [mix(1, 0, 1), mix(0, 1, 1), mix(1, 1, 2), RGBA8::TRANSPARENT]
+    };
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    let mut pixels = [RGBA8::TRANSPARENT; 16];
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        *pixel = palette[(indices >> (i * 2)) as usize & 3];
+    }
+    pixels
+}
+
+/// Decodes an 8 byte interpolated channel block, as used by BC3 alpha, BC4, and BC5.
+fn decode_channel(block: &[u8]) -> [u8; 16] {
+    let (a0, a1) = (block[0] as u32, block[1] as u32);
+    let mut palette = [a0, a1, 0, 0, 0, 0, 0, 255];
+    if a0 > a1 {
+        for (i, value) in palette.iter_mut().enumerate().skip(2) {
+            *value = ((8 - i as u32) * a0 + (i as u32 - 1) * a1) / 7;
+        }
+    } else {
+        for (i, value) in palette.iter_mut().enumerate().take(6).skip(2) {
+            *value = ((6 - i as u32) * a0 + (i as u32 - 1) * a1) / 5;
+        }
+    }
+    let mut bits = [0u8; 8];
+    bits[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bits);
+    let mut values = [0u8; 16];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = palette[(indices >> (i * 3)) as usize & 7] as u8;
+    }
+    values
+}
+
+/// Decodes a BC1 block into 4x4 pixels in row order.
+pub(crate) fn decode_bc1(block: &[u8]) -> [RGBA8; 16] {
+    decode_color(block, true)
+}
+
+/// Decodes a BC2 block into 4x4 pixels in row order.
+pub(crate) fn decode_bc2(block: &[u8]) -> [RGBA8; 16] {
+    let mut pixels = decode_color(&block[8..], false);
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        pixel.a = ((block[i / 2] >> ((i % 2) * 4)) & 0xf) * 17;
+    }
+    pixels
+}
+
+/// Decodes a BC3 block into 4x4 pixels in row order.
+pub(crate) fn decode_bc3(block: &[u8]) -> [RGBA8; 16] {
+    let mut pixels = decode_color(&block[8..], false);
+    for (pixel, alpha) in pixels.iter_mut().zip(decode_channel(block)) {
+        pixel.a = alpha;
+    }
+    pixels
+}
+
+/// Decodes a BC4 block into 4x4 pixels in row order, with the channel in red.
+pub(crate) fn decode_bc4(block: &[u8]) -> [RGBA8; 16] {
+    decode_channel(block).map(|r| RGBA8::new(r, 0, 0, 255))
+}
+
+/// Decodes a BC5 block into 4x4 pixels in row order, with the channels in red and green.
+pub(crate) fn decode_bc5(block: &[u8]) -> [RGBA8; 16] {
+    let (red, green) = (decode_channel(block), decode_channel(&block[8..]));
+    core::array::from_fn(|i| RGBA8::new(red[i], green[i], 0, 255))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bc1_modes() {
+        // Four color mode between white and black, pixels 0..4 use each palette entry.
+        let pixels = decode_bc1(&[0xff, 0xff, 0x00, 0x00, 0b11100100, 0, 0, 0]);
+        assert_eq!(pixels[0], RGBA8::WHITE);
+        assert_eq!(pixels[1], RGBA8::BLACK);
+        assert_eq!(pixels[2], RGBA8::new(170, 170, 170, 255));
+        assert_eq!(pixels[3], RGBA8::new(85, 85, 85, 255));
+
+        // Three color mode, the fourth entry is transparent.
+        let pixels = decode_bc1(&[0x00, 0x00, 0xff, 0xff, 0b11100100, 0, 0, 0]);
+        assert_eq!(pixels[2], RGBA8::new(127, 127, 127, 255));
+        assert_eq!(pixels[3], RGBA8::TRANSPARENT);
+    }
+
+    #[test]
+    fn bc3_alpha() {
+        // Alpha interpolates from 255 to 0 in eight steps. The color block is solid white.
+        let mut block = [255, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0];
+        block[2] = 0b1010_0000; // Pixel 1 uses index 4, pixel 0 uses index 0.
+        let pixels = decode_bc3(&block);
+        assert_eq!(pixels[0], RGBA8::WHITE);
+        assert_eq!(pixels[1], RGBA8::new(255, 255, 255, 145));
+        assert_eq!(decode_bc4(&block[..8])[1], RGBA8::new(145, 0, 0, 255));
+    }
+}
@@ -0,0 +1,280 @@
+use super::format::checked_image;
+use super::{Image, ImageError};
+use crate::color::RGBA8;
+use alloc::vec::Vec;
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, ImageError> {
+    let bytes = bytes.get(offset..offset + 2).ok_or(ImageError::InvalidData)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, ImageError> {
+    let bytes = bytes.get(offset..offset + 4).ok_or(ImageError::InvalidData)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Extracts a channel from a pixel using a bit mask, scaled to 8 bits.
+#[derive(Copy, Clone)]
+struct Mask {
+    mask: u32,
+    shift: u32,
+    max: u32,
+}
+
+impl Mask {
+    fn new(mask: u32) -> Mask {
+        let shift = if mask == 0 {
+            0
+        } else {
+            mask.trailing_zeros()
+        };
+        let bits = (mask >> shift).count_ones();
+        Mask {
+            mask,
+            shift,
+            max: if bits >= 32 {
+                u32::MAX
+            } else {
+                (1 << bits) - 1
+            },
+        }
+    }
+
+    fn extract(&self, pixel: u32, default: u8) -> u8 {
+        if self.mask == 0 {
+            return default;
+        }
+        let value = (pixel & self.mask) >> self.shift;
+        ((value as u64 * 255 + self.max as u64 / 2) / self.max as u64) as u8
+    }
+}
+
+/// Decodes an uncompressed BMP. Palettized 1, 4, and 8 bit images, and 16, 24, and 32 bit images,
+/// including those with bit field masks, are supported. RLE and embedded JPEG or PNG payloads are
+/// not.
+pub fn read_bmp(bytes: &[u8]) -> Result<Image<RGBA8>, ImageError> {
+    if !bytes.starts_with(b"BM") {
+        return Err(ImageError::InvalidData);
+    }
+    let data_offset = u32_at(bytes, 10)? as usize;
+    let header_size = u32_at(bytes, 14)? as usize;
+
+    let (width, height, bpp, compression, colors_used, palette_entry) = if header_size == 12 {
+        let width = u16_at(bytes, 18)? as i64;
+        let height = u16_at(bytes, 20)? as i64;
+        (width, height, u16_at(bytes, 24)?, BI_RGB, 0, 3)
+    } else if header_size >= 40 {
+        let width = u32_at(bytes, 18)? as i32 as i64;
+        let height = u32_at(bytes, 22)? as i32 as i64;
+        (width, height, u16_at(bytes, 28)?, u32_at(bytes, 30)?, u32_at(bytes, 46)?, 4)
+    } else {
+        return Err(ImageError::Unsupported);
+    };
+    // Negative heights are stored top to bottom.
+    let top_down = height < 0;
+    let height = height.abs();
+    if width <= 0 || height == 0 || width > u32::MAX as i64 || height > u32::MAX as i64 {
+        return Err(ImageError::InvalidDimensions);
+    }
+    let (width, height) = (width as u32, height as u32);
+    super::format::check_dimensions(width, height)?;
+
+    // Masks follow a 40 byte header, and are part of larger headers.
+    let masks_offset = 14 + 40;
+    let mut palette_offset = 14 + header_size;
+    let masks = match (compression, bpp) {
+        (BI_BITFIELDS, 16) | (BI_BITFIELDS, 32) | (BI_ALPHABITFIELDS, 16) | (BI_ALPHABITFIELDS, 32) => {
+            let alpha = if compression == BI_ALPHABITFIELDS || header_size >= 56 {
+                u32_at(bytes, masks_offset + 12)?
+            } else {
+                0
+            };
+            if header_size == 40 {
+                palette_offset += if compression == BI_ALPHABITFIELDS {
+                    16
+                } else {
+                    12
+                };
+            }
+            [
+                Mask::new(u32_at(bytes, masks_offset)?),
+                Mask::new(u32_at(bytes, masks_offset + 4)?),
+                Mask::new(u32_at(bytes, masks_offset + 8)?),
+                Mask::new(alpha),
+            ]
+        }
+        (BI_RGB, 16) => [Mask::new(0x7c00), Mask::new(0x03e0), Mask::new(0x001f), Mask::new(0)],
+        (BI_RGB, 32) => [Mask::new(0x00ff0000), Mask::new(0x0000ff00), Mask::new(0x000000ff), Mask::new(0)],
+        (BI_RGB, 1) | (BI_RGB, 4) | (BI_RGB, 8) | (BI_RGB, 24) => [Mask::new(0); 4],
+        _ => return Err(ImageError::Unsupported),
+    };
+
+    let palette = if bpp <= 8 {
+        let count = if colors_used == 0 {
+            1usize << bpp
+        } else {
+            (colors_used as usize).min(1 << bpp)
+        };
+        let table = bytes
+            .get(palette_offset..palette_offset + count * palette_entry)
+            .ok_or(ImageError::InvalidData)?;
+        table.chunks_exact(palette_entry).map(|bgr| RGBA8::new(bgr[2], bgr[1], bgr[0], 255)).collect()
+    } else {
+        Vec::new()
+    };
+
+    let stride = (width as usize * bpp as usize).div_ceil(32) * 4;
+    let data = bytes.get(data_offset..).ok_or(ImageError::InvalidData)?;
+    if data.len() < stride * height as usize {
+        return Err(ImageError::InvalidData);
+    }
+
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height as usize {
+        let row = if top_down {
+            y
+        } else {
+            height as usize - 1 - y
+        };
+        let row = &data[row * stride..(row + 1) * stride];
+        for x in 0..width as usize {
+            let pixel = match bpp {
+                1 | 4 | 8 => {
+                    let bit = x * bpp as usize;
+                    let byte = row[bit / 8];
+                    let index = (byte >> (8 - bpp as usize - bit % 8)) & ((1u16 << bpp) - 1) as u8;
+                    *palette.get(index as usize).ok_or(ImageError::InvalidData)?
+                }
+                24 => RGBA8::new(row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 255),
+                16 | 32 => {
+                    let size = bpp as usize / 8;
+                    let mut value = [0u8; 4];
+                    value[..size].copy_from_slice(&row[x * size..x * size + size]);
+                    let value = u32::from_le_bytes(value);
+                    RGBA8::new(
+                        masks[0].extract(value, 0),
+                        masks[1].extract(value, 0),
+                        masks[2].extract(value, 0),
+                        masks[3].extract(value, 255),
+                    )
+                }
+                _ => return Err(ImageError::Unsupported),
+            };
+            pixels.push(pixel);
+        }
+    }
+    checked_image(pixels, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(width: i32, height: i32, bpp: u16, compression: u32) -> Vec<u8> {
+        let mut info = Vec::new();
+        info.extend_from_slice(&40u32.to_le_bytes());
+        info.extend_from_slice(&width.to_le_bytes());
+        info.extend_from_slice(&height.to_le_bytes());
+        info.extend_from_slice(&1u16.to_le_bytes());
+        info.extend_from_slice(&bpp.to_le_bytes());
+        info.extend_from_slice(&compression.to_le_bytes());
+        info.resize(40, 0);
+        info
+    }
+
+    fn bmp(info: &[u8], extra: &[u8], data: &[u8]) -> Vec<u8> {
+        let data_offset = 14 + info.len() + extra.len();
+        let mut bytes = b"BM".to_vec();
+        bytes.extend_from_slice(&((data_offset + data.len()) as u32).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&(data_offset as u32).to_le_bytes());
+        [&bytes, info, extra, data].concat()
+    }
+
+    const PALETTE: [u8; 12] = [0, 0, 255, 0, 0, 255, 0, 0, 255, 0, 0, 0];
+
+    #[test]
+    fn palettized() {
+        // 1 bit indices cross into the second byte of the row.
+        let palette = [0, 0, 0, 0, 255, 255, 255, 0];
+        let image =
+            read_bmp(&bmp(&info(10, 1, 1, BI_RGB), &palette, &[0b1010_1010, 0b1100_0000, 0, 0])).unwrap();
+        let white = |x: usize| x % 2 == 0 || x == 9;
+        assert!((0..10).all(|x| image.get(x as u32, 0)
+            == if white(x) {
+                RGBA8::WHITE
+            } else {
+                RGBA8::BLACK
+            }));
+
+        // 4 bit indices, with a palette shorter than 16 entries.
+        let mut header = info(3, 1, 4, BI_RGB);
+        header[32..36].copy_from_slice(&3u32.to_le_bytes());
+        let image = read_bmp(&bmp(&header, &PALETTE, &[0x01, 0x20, 0, 0])).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::RED, RGBA8::GREEN, RGBA8::BLUE]);
+        let out_of_range = bmp(&header, &PALETTE, &[0x03, 0x00, 0, 0]);
+        assert_eq!(read_bmp(&out_of_range).err(), Some(ImageError::InvalidData));
+
+        // The palette follows larger headers.
+        let mut header = info(1, 1, 8, BI_RGB);
+        header.resize(108, 0);
+        header[0..4].copy_from_slice(&108u32.to_le_bytes());
+        header[32..36].copy_from_slice(&3u32.to_le_bytes());
+        let image = read_bmp(&bmp(&header, &PALETTE, &[2, 0, 0, 0])).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::BLUE]);
+    }
+
+    #[test]
+    fn row_order() {
+        let rows = [0, 0, 0, 0, 2, 0, 0, 0];
+        let header = |height: i32| {
+            let mut header = info(1, height, 8, BI_RGB);
+            header[32..36].copy_from_slice(&3u32.to_le_bytes());
+            header
+        };
+        let bottom_up = read_bmp(&bmp(&header(2), &PALETTE, &rows)).unwrap();
+        assert_eq!(bottom_up.as_slice(), [RGBA8::BLUE, RGBA8::RED]);
+        let top_down = read_bmp(&bmp(&header(-2), &PALETTE, &rows)).unwrap();
+        assert_eq!(top_down.as_slice(), [RGBA8::RED, RGBA8::BLUE]);
+    }
+
+    #[test]
+    fn sixteen_bit() {
+        // 555 by default, scaled from 5 bits to 8.
+        let image = read_bmp(&bmp(&info(2, 1, 16, BI_RGB), &[], &[0x00, 0x7c, 0x1f, 0x00])).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::RED, RGBA8::BLUE]);
+
+        // 565 through bit field masks.
+        let masks = [0x00, 0xf8, 0, 0, 0xe0, 0x07, 0, 0, 0x1f, 0, 0, 0];
+        let data = [0xe0, 0x07, 0x10, 0x84];
+        let image = read_bmp(&bmp(&info(2, 1, 16, BI_BITFIELDS), &masks, &data)).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::GREEN, RGBA8::new(132, 130, 132, 255)]);
+    }
+
+    #[test]
+    fn alpha_bit_fields() {
+        let masks = [0, 0xff, 0, 0, 0, 0, 0xff, 0, 0, 0, 0, 0xff, 0xff, 0, 0, 0];
+        let image = read_bmp(&bmp(&info(1, 1, 32, BI_ALPHABITFIELDS), &masks, &[0x80, 10, 20, 30])).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::new(10, 20, 30, 128)]);
+    }
+
+    #[test]
+    fn truncated() {
+        let masks = [0x00, 0xf8, 0, 0, 0xe0, 0x07, 0, 0, 0x1f, 0, 0, 0];
+        let files = [
+            bmp(&info(10, 1, 1, BI_RGB), &[0, 0, 0, 0, 255, 255, 255, 0], &[0xff, 0xff, 0, 0]),
+            bmp(&info(2, 2, 16, BI_BITFIELDS), &masks, &[0; 8]),
+            bmp(&info(1, 1, 24, BI_RGB), &[], &[0; 4]),
+        ];
+        for file in &files {
+            assert!(read_bmp(file).is_ok());
+            for len in 0..file.len() {
+                assert_eq!(read_bmp(&file[..len]).err(), Some(ImageError::InvalidData), "{} bytes", len);
+            }
+        }
+    }
+}
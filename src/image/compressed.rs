@@ -0,0 +1,316 @@
+use super::{Image, ImageError};
+use crate::color::RGBA8;
+use crate::graphics::CompressedInternalFormat;
+use alloc::vec::Vec;
+
+/// The block compressed pixel formats a compressed image can hold.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CompressedFormat {
+    /// BC1 (DXT1). RGB with 1 bit alpha, 8 bytes per 4x4 block.
+    Bc1,
+    /// BC2 (DXT3). RGB with explicit 4 bit alpha, 16 bytes per 4x4 block.
+    Bc2,
+    /// BC3 (DXT5). RGB with interpolated alpha, 16 bytes per 4x4 block.
+    Bc3,
+    /// BC4 (RGTC1). Single unsigned red channel, 8 bytes per 4x4 block.
+    Bc4,
+    /// BC5 (RGTC2). Unsigned red and green channels, 16 bytes per 4x4 block.
+    Bc5,
+    /// BC6H (BPTC). Unsigned half float RGB, 16 bytes per 4x4 block.
+    Bc6h,
+    /// BC7 (BPTC). RGBA, 16 bytes per 4x4 block.
+    Bc7,
+    /// ETC2 RGB, 8 bytes per 4x4 block. This is backwards compatible with ETC1.
+    Etc2Rgb,
+    /// ETC2 RGB with punchthrough 1 bit alpha, 8 bytes per 4x4 block.
+    Etc2RgbA1,
+    /// ETC2 RGB with EAC alpha, 16 bytes per 4x4 block.
+    Etc2Rgba,
+    /// ASTC LDR with 4x4 blocks. Every ASTC format uses 16 bytes per block of the named size.
+    Astc4x4,
+    Astc5x4,
+    Astc5x5,
+    Astc6x5,
+    Astc6x6,
+    Astc8x5,
+    Astc8x6,
+    Astc8x8,
+    Astc10x5,
+    Astc10x6,
+    Astc10x8,
+    Astc10x10,
+    Astc12x10,
+    Astc12x12,
+}
+
+impl CompressedFormat {
+    /// The width and height in pixels of a single block.
+    pub fn block_size(&self) -> (u32, u32) {
+        match self {
+            CompressedFormat::Astc5x4 => (5, 4),
+            CompressedFormat::Astc5x5 => (5, 5),
+            CompressedFormat::Astc6x5 => (6, 5),
+            CompressedFormat::Astc6x6 => (6, 6),
+            CompressedFormat::Astc8x5 => (8, 5),
+            CompressedFormat::Astc8x6 => (8, 6),
+            CompressedFormat::Astc8x8 => (8, 8),
+            CompressedFormat::Astc10x5 => (10, 5),
+            CompressedFormat::Astc10x6 => (10, 6),
+            CompressedFormat::Astc10x8 => (10, 8),
+            CompressedFormat::Astc10x10 => (10, 10),
+            CompressedFormat::Astc12x10 => (12, 10),
+            CompressedFormat::Astc12x12 => (12, 12),
+            _ => (4, 4),
+        }
+    }
+
+    /// The size in bytes of a single block.
+    pub fn block_bytes(&self) -> usize {
+        match self {
+            CompressedFormat::Bc1
+            | CompressedFormat::Bc4
+            | CompressedFormat::Etc2Rgb
+            | CompressedFormat::Etc2RgbA1 => 8,
+            _ => 16,
+        }
+    }
+
+    /// Returns true if the format can be decoded on the CPU. BC6H, BC7, and ASTC can only be
+    /// used on GPUs that support them.
+    pub fn is_decodable(&self) -> bool {
+        matches!(
+            self,
+            CompressedFormat::Bc1
+                | CompressedFormat::Bc2
+                | CompressedFormat::Bc3
+                | CompressedFormat::Bc4
+                | CompressedFormat::Bc5
+                | CompressedFormat::Etc2Rgb
+                | CompressedFormat::Etc2RgbA1
+                | CompressedFormat::Etc2Rgba
+        )
+    }
+
+    /// The internal format the GPU stores the format in. BC4, BC5, and BC6H have no sRGB variant,
+    /// so they're always linear.
+    pub(crate) fn gpu_format(&self, srgb: bool) -> CompressedInternalFormat {
+        use CompressedInternalFormat as Gpu;
+        match (self, srgb) {
+            (CompressedFormat::Bc1, false) => Gpu::RGBAS3tcDxt1,
+            (CompressedFormat::Bc1, true) => Gpu::SRGBAlphaS3tcDxt1,
+            (CompressedFormat::Bc2, false) => Gpu::RGBAS3tcDxt3,
+            (CompressedFormat::Bc2, true) => Gpu::SRGBAlphaS3tcDxt3,
+            (CompressedFormat::Bc3, false) => Gpu::RGBAS3tcDxt5,
+            (CompressedFormat::Bc3, true) => Gpu::SRGBAlphaS3tcDxt5,
+            (CompressedFormat::Bc4, _) => Gpu::RedRgtc1,
+            (CompressedFormat::Bc5, _) => Gpu::RGRgtc2,
+            (CompressedFormat::Bc6h, _) => Gpu::RGBBptcUnsignedFloat,
+            (CompressedFormat::Bc7, false) => Gpu::RGBABptcUnorm,
+            (CompressedFormat::Bc7, true) => Gpu::SRGBAlphaBptcUnorm,
+            (CompressedFormat::Etc2Rgb, false) => Gpu::RGB8Etc2,
+            (CompressedFormat::Etc2Rgb, true) => Gpu::SRGB8Etc2,
+            (CompressedFormat::Etc2RgbA1, false) => Gpu::RGB8PunchthroughAlpha1Etc2,
+            (CompressedFormat::Etc2RgbA1, true) => Gpu::SRGB8PunchthroughAlpha1Etc2,
+            (CompressedFormat::Etc2Rgba, false) => Gpu::RGBA8Etc2Eac,
+            (CompressedFormat::Etc2Rgba, true) => Gpu::SRGB8Alpha8Etc2Eac,
+            (CompressedFormat::Astc4x4, false) => Gpu::RGBAAstc4x4,
+            (CompressedFormat::Astc4x4, true) => Gpu::SRGB8Alpha8Astc4x4,
+            (CompressedFormat::Astc5x4, false) => Gpu::RGBAAstc5x4,
+            (CompressedFormat::Astc5x4, true) => Gpu::SRGB8Alpha8Astc5x4,
+            (CompressedFormat::Astc5x5, false) => Gpu::RGBAAstc5x5,
+            (CompressedFormat::Astc5x5, true) => Gpu::SRGB8Alpha8Astc5x5,
+            (CompressedFormat::Astc6x5, false) => Gpu::RGBAAstc6x5,
+            (CompressedFormat::Astc6x5, true) => Gpu::SRGB8Alpha8Astc6x5,
+            (CompressedFormat::Astc6x6, false) => Gpu::RGBAAstc6x6,
+            (CompressedFormat::Astc6x6, true) => Gpu::SRGB8Alpha8Astc6x6,
+            (CompressedFormat::Astc8x5, false) => Gpu::RGBAAstc8x5,
+            (CompressedFormat::Astc8x5, true) => Gpu::SRGB8Alpha8Astc8x5,
+            (CompressedFormat::Astc8x6, false) => Gpu::RGBAAstc8x6,
+            (CompressedFormat::Astc8x6, true) => Gpu::SRGB8Alpha8Astc8x6,
+            (CompressedFormat::Astc8x8, false) => Gpu::RGBAAstc8x8,
+            (CompressedFormat::Astc8x8, true) => Gpu::SRGB8Alpha8Astc8x8,
+            (CompressedFormat::Astc10x5, false) => Gpu::RGBAAstc10x5,
+            (CompressedFormat::Astc10x5, true) => Gpu::SRGB8Alpha8Astc10x5,
+            (CompressedFormat::Astc10x6, false) => Gpu::RGBAAstc10x6,
+            (CompressedFormat::Astc10x6, true) => Gpu::SRGB8Alpha8Astc10x6,
+            (CompressedFormat::Astc10x8, false) => Gpu::RGBAAstc10x8,
+            (CompressedFormat::Astc10x8, true) => Gpu::SRGB8Alpha8Astc10x8,
+            (CompressedFormat::Astc10x10, false) => Gpu::RGBAAstc10x10,
+            (CompressedFormat::Astc10x10, true) => Gpu::SRGB8Alpha8Astc10x10,
+            (CompressedFormat::Astc12x10, false) => Gpu::RGBAAstc12x10,
+            (CompressedFormat::Astc12x10, true) => Gpu::SRGB8Alpha8Astc12x10,
+            (CompressedFormat::Astc12x12, false) => Gpu::RGBAAstc12x12,
+            (CompressedFormat::Astc12x12, true) => Gpu::SRGB8Alpha8Astc12x12,
+        }
+    }
+
+    /// The size in bytes of a mip level with the given dimensions.
+    pub(crate) fn level_size(&self, width: u32, height: u32) -> usize {
+        let (block_width, block_height) = self.block_size();
+        width.div_ceil(block_width) as usize * height.div_ceil(block_height) as usize * self.block_bytes()
+    }
+}
+
+/// An image stored in a GPU block compressed format, along with its mip chain. The data is kept
+/// compressed so it can be uploaded to the GPU as is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompressedImage {
+    format: CompressedFormat,
+    srgb: bool,
+    width: u32,
+    height: u32,
+    levels: Vec<Vec<u8>>,
+}
+
+impl CompressedImage {
+    /// Interpret a slice of bytes as a KTX2 or DDS container. Supercompressed KTX2 files, cube
+    /// maps, arrays, and 3D textures aren't supported.
+    pub fn from_bytes(bytes: &[u8]) -> Result<CompressedImage, ImageError> {
+        if super::ktx2::is_ktx2(bytes) {
+            super::ktx2::read_ktx2(bytes)
+        } else if super::dds::is_dds(bytes) {
+            super::dds::read_dds(bytes)
+        } else {
+            Err(ImageError::UnknownFormat)
+        }
+    }
+
+    /// Creates a compressed image from the data of each mip level, starting with the full size
+    /// image. Each level is half the size of the previous one, to a minimum of 1 pixel. Data past
+    /// the expected size of a level is ignored.
+    pub(crate) fn from_levels(
+        format: CompressedFormat,
+        srgb: bool,
+        width: u32,
+        height: u32,
+        levels: Vec<&[u8]>,
+    ) -> Result<CompressedImage, ImageError> {
+        super::format::check_dimensions(width, height)?;
+        if levels.is_empty() || levels.len() > 32 {
+            return Err(ImageError::InvalidData);
+        }
+        let levels = levels
+            .iter()
+            .enumerate()
+            .map(|(level, data)| {
+                let size = format.level_size((width >> level).max(1), (height >> level).max(1));
+                data.get(..size).map(|data| data.to_vec()).ok_or(ImageError::InvalidData)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CompressedImage {
+            format,
+            srgb,
+            width,
+            height,
+            levels,
+        })
+    }
+
+    /// The block compressed format of the image.
+    pub fn format(&self) -> CompressedFormat {
+        self.format
+    }
+
+    /// Returns true if the container marks the color data as sRGB encoded.
+    pub fn is_srgb(&self) -> bool {
+        self.srgb
+    }
+
+    /// The width of the full size image.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the full size image.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The compressed data of each mip level, starting with the full size image.
+    pub fn levels(&self) -> &[Vec<u8>] {
+        &self.levels
+    }
+
+    /// Gets the width and height of a mip level.
+    pub fn level_dimensions(&self, level: usize) -> (u32, u32) {
+        ((self.width >> level).max(1), (self.height >> level).max(1))
+    }
+
+    /// Decodes every mip level into an RGBA image. Single channel formats decode into red, and
+    /// two channel formats into red and green, matching how the GPU samples them. Returns
+    /// `ImageError::Unsupported` if the format can't be decoded on the CPU.
+    pub fn decode(&self) -> Result<Vec<Image<RGBA8>>, ImageError> {
+        if !self.format.is_decodable() {
+            return Err(ImageError::Unsupported);
+        }
+        Ok((0..self.levels.len()).map(|level| self.decode_level(level)).collect())
+    }
+
+    fn decode_level(&self, level: usize) -> Image<RGBA8> {
+        let (width, height) = self.level_dimensions(level);
+        let mut image = Image::from_color(RGBA8::TRANSPARENT, width, height);
+        let blocks_wide = width.div_ceil(4);
+        let block_bytes = self.format.block_bytes();
+        for (index, block) in self.levels[level].chunks_exact(block_bytes).enumerate() {
+            let pixels = match self.format {
+                CompressedFormat::Bc1 => super::bc::decode_bc1(block),
+                CompressedFormat::Bc2 => super::bc::decode_bc2(block),
+                CompressedFormat::Bc3 => super::bc::decode_bc3(block),
+                CompressedFormat::Bc4 => super::bc::decode_bc4(block),
+                CompressedFormat::Bc5 => super::bc::decode_bc5(block),
+                CompressedFormat::Etc2Rgb => super::etc::decode_etc2_rgb(block, false),
+                CompressedFormat::Etc2RgbA1 => super::etc::decode_etc2_rgb(block, true),
+                _ => super::etc::decode_etc2_rgba(block),
+            };
+            let (left, top) = ((index as u32 % blocks_wide) * 4, (index as u32 / blocks_wide) * 4);
+            for (offset, pixel) in pixels.iter().enumerate() {
+                let (x, y) = (left + offset as u32 % 4, top + offset as u32 / 4);
+                if x < width && y < height {
+                    image.set(x, y, *pixel);
+                }
+            }
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_sizes() {
+        assert_eq!(CompressedFormat::Bc1.level_size(7, 5), 2 * 2 * 8);
+        assert_eq!(CompressedFormat::Bc7.level_size(1, 1), 16);
+        assert_eq!(CompressedFormat::Astc10x8.level_size(20, 9), 2 * 2 * 16);
+    }
+
+    #[test]
+    fn srgb_gpu_formats() {
+        use CompressedInternalFormat as Gpu;
+        assert_eq!(CompressedFormat::Bc3.gpu_format(true), Gpu::SRGBAlphaS3tcDxt5);
+        assert_eq!(CompressedFormat::Bc7.gpu_format(true), Gpu::SRGBAlphaBptcUnorm);
+        assert_eq!(CompressedFormat::Etc2RgbA1.gpu_format(true), Gpu::SRGB8PunchthroughAlpha1Etc2);
+        assert_eq!(CompressedFormat::Astc12x12.gpu_format(true) as u32, 0x93DD);
+        assert_eq!(CompressedFormat::Astc12x12.gpu_format(false), Gpu::RGBAAstc12x12);
+        assert_eq!(CompressedFormat::Bc5.gpu_format(true), Gpu::RGRgtc2);
+    }
+
+    #[test]
+    fn decode_clips_partial_blocks() {
+        // Two BC1 blocks, solid red then solid blue, decoded at 6x2.
+        let red = [0x00, 0xf8, 0x00, 0xf8, 0, 0, 0, 0];
+        let blue = [0x1f, 0x00, 0x1f, 0x00, 0, 0, 0, 0];
+        let data = [red, blue].concat();
+        let image =
+            CompressedImage::from_levels(CompressedFormat::Bc1, false, 6, 2, alloc::vec![&data]).unwrap();
+        let decoded = &image.decode().unwrap()[0];
+        assert_eq!((decoded.width(), decoded.height()), (6, 2));
+        assert_eq!(decoded.get(3, 1), RGBA8::RED);
+        assert_eq!(decoded.get(5, 0), RGBA8::BLUE);
+        assert_eq!(
+            CompressedImage::from_levels(CompressedFormat::Bc1, false, 6, 2, alloc::vec![&red]).err(),
+            Some(ImageError::InvalidData)
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::color::{Channel, ColorConvert, ColorDescriptor, R8, RGBA8};
+use crate::image::Image;
+
+impl<T: ColorDescriptor> Image<T> {
+    /// Creates a new image by applying the function to every pixel.
+    pub fn map<U: ColorDescriptor>(&self, f: impl Fn(T) -> U) -> Image<U> {
+        Image::from_vec(self.as_slice().iter().map(|&pixel| f(pixel)).collect(), self.width(), self.height())
+    }
+}
+
+impl<T: ColorConvert> Image<T> {
+    /// Converts the image to another color type using the target's default channel mapping. Gray
+    /// targets take the luma of the image, and gray with alpha targets also take its alpha.
+    pub fn convert<U: ColorConvert>(&self) -> Image<U> {
+        self.map(|pixel| pixel.convert())
+    }
+
+    /// Converts the image to another color type, filling each component of the target from the
+    /// channel at the same position in the mapping.
+    pub fn convert_with<U: ColorConvert>(&self, mapping: [Channel; 4]) -> Image<U> {
+        self.map(|pixel| pixel.convert_with(mapping))
+    }
+
+    /// Creates a single channel image from one channel of this image, such as its alpha as a mask.
+    pub fn extract_channel(&self, channel: Channel) -> Image<R8> {
+        self.map(|pixel| R8::new(channel.read(pixel.to_rgba8())))
+    }
+}
+
+impl Image<RGBA8> {
+    /// Multiplies the color channels of every pixel by its alpha, in place.
+    pub fn premultiply(&mut self) {
+        self.as_mut_slice().iter_mut().for_each(|pixel| *pixel = pixel.premultiplied());
+    }
+
+    /// Divides the color channels of every pixel by its alpha, in place, reversing `premultiply`.
+    pub fn unpremultiply(&mut self) {
+        self.as_mut_slice().iter_mut().for_each(|pixel| *pixel = pixel.unpremultiplied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{RG8, RGB8};
+
+    #[test]
+    fn conversions() {
+        let image = Image::from_vec(alloc::vec![RGBA8::new(200, 100, 50, 128)], 1, 1);
+        assert_eq!(image.convert::<R8>().as_slice(), [R8::new(124)]);
+        assert_eq!(image.convert::<RG8>().as_slice(), [RG8::new(124, 128)]);
+        assert_eq!(image.convert::<RGB8>().as_slice(), [RGB8::new(200, 100, 50)]);
+        assert_eq!(image.extract_channel(Channel::Alpha).as_slice(), [R8::new(128)]);
+
+        let swizzled =
+            image.convert_with::<RGBA8>([Channel::Blue, Channel::Green, Channel::Red, Channel::Alpha]);
+        assert_eq!(swizzled.as_slice(), [RGBA8::new(50, 100, 200, 128)]);
+        assert_eq!(image.convert::<R8>().convert::<RGBA8>().as_slice(), [RGBA8::new(124, 124, 124, 255)]);
+    }
+
+    #[test]
+    fn premultiply() {
+        let mut image =
+            Image::from_vec(alloc::vec![RGBA8::new(200, 100, 50, 128), RGBA8::new(9, 9, 9, 0)], 2, 1);
+        image.premultiply();
+        assert_eq!(image.as_slice(), [RGBA8::new(100, 50, 25, 128), RGBA8::TRANSPARENT]);
+        image.unpremultiply();
+        assert_eq!(image.as_slice(), [RGBA8::new(199, 100, 50, 128), RGBA8::TRANSPARENT]);
+    }
+}
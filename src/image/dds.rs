@@ -0,0 +1,133 @@
+use super::compressed::{CompressedFormat, CompressedImage};
+use super::ImageError;
+use alloc::vec::Vec;
+
+const HEADER_SIZE: usize = 128;
+const DX10_HEADER_SIZE: usize = 20;
+const DDPF_FOURCC: u32 = 0x4;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, ImageError> {
+    let bytes = bytes.get(offset..offset + 4).ok_or(ImageError::InvalidData)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Returns true if the bytes start with the DDS magic number.
+pub(crate) fn is_dds(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"DDS ")
+}
+
+/// Maps a DXGI format to a compressed format, and whether it's sRGB encoded. Typeless formats are
+/// read as UNORM.
+fn dxgi_format(format: u32) -> Option<(CompressedFormat, bool)> {
+    Some(match format {
+        70 | 71 => (CompressedFormat::Bc1, false),
+        72 => (CompressedFormat::Bc1, true),
+        73 | 74 => (CompressedFormat::Bc2, false),
+        75 => (CompressedFormat::Bc2, true),
+        76 | 77 => (CompressedFormat::Bc3, false),
+        78 => (CompressedFormat::Bc3, true),
+        79 | 80 => (CompressedFormat::Bc4, false),
+        82 | 83 => (CompressedFormat::Bc5, false),
+        94 | 95 => (CompressedFormat::Bc6h, false),
+        97 | 98 => (CompressedFormat::Bc7, false),
+        99 => (CompressedFormat::Bc7, true),
+        _ => return None,
+    })
+}
+
+/// Decodes a DDS container holding a single 2D image with BC1 to BC7 data, identified either by a
+/// legacy four character code or a DX10 header.
+pub(crate) fn read_dds(bytes: &[u8]) -> Result<CompressedImage, ImageError> {
+    if !is_dds(bytes) || u32_at(bytes, 4)? != 124 {
+        return Err(ImageError::InvalidData);
+    }
+    let height = u32_at(bytes, 12)?;
+    let width = u32_at(bytes, 16)?;
+    // Checked before level sizes are computed from the dimensions.
+    super::format::check_dimensions(width, height)?;
+    let level_count = u32_at(bytes, 28)?.max(1) as usize;
+    let pixel_flags = u32_at(bytes, 80)?;
+    let caps2 = u32_at(bytes, 112)?;
+    if pixel_flags & DDPF_FOURCC == 0 || caps2 & (DDSCAPS2_CUBEMAP | DDSCAPS2_VOLUME) != 0 {
+        return Err(ImageError::Unsupported);
+    }
+
+    let mut offset = HEADER_SIZE;
+    let (format, srgb) = match bytes.get(84..88).ok_or(ImageError::InvalidData)? {
+        b"DXT1" => (CompressedFormat::Bc1, false),
+        b"DXT2" | b"DXT3" => (CompressedFormat::Bc2, false),
+        b"DXT4" | b"DXT5" => (CompressedFormat::Bc3, false),
+        b"ATI1" | b"BC4U" => (CompressedFormat::Bc4, false),
+        b"ATI2" | b"BC5U" => (CompressedFormat::Bc5, false),
+        b"DX10" => {
+            // Resource dimension 3 is a 2D texture.
+            if u32_at(bytes, 132)? != 3 || u32_at(bytes, 140)? > 1 {
+                return Err(ImageError::Unsupported);
+            }
+            offset += DX10_HEADER_SIZE;
+            dxgi_format(u32_at(bytes, 128)?).ok_or(ImageError::Unsupported)?
+        }
+        _ => return Err(ImageError::Unsupported),
+    };
+
+    let mut levels = Vec::with_capacity(level_count.min(32));
+    for level in 0..level_count.min(32) {
+        let size = format.level_size((width >> level).max(1), (height >> level).max(1));
+        let end = offset.checked_add(size).ok_or(ImageError::InvalidData)?;
+        levels.push(bytes.get(offset..end).ok_or(ImageError::InvalidData)?);
+        offset = end;
+    }
+    CompressedImage::from_levels(format, srgb, width, height, levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dds(four_cc: &[u8; 4], width: u32, height: u32, levels: u32) -> Vec<u8> {
+        let mut bytes = b"DDS ".to_vec();
+        bytes.resize(HEADER_SIZE, 0);
+        bytes[4..8].copy_from_slice(&124u32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&height.to_le_bytes());
+        bytes[16..20].copy_from_slice(&width.to_le_bytes());
+        bytes[28..32].copy_from_slice(&levels.to_le_bytes());
+        bytes[80..84].copy_from_slice(&DDPF_FOURCC.to_le_bytes());
+        bytes[84..88].copy_from_slice(four_cc);
+        bytes
+    }
+
+    #[test]
+    fn legacy_mip_chain() {
+        // 8x8 BC3 has levels of 4, 1, and 1 blocks.
+        let mut bytes = dds(b"DXT5", 8, 8, 4);
+        bytes.extend((0..7 * 16).map(|i| (i / 16) as u8));
+        let image = CompressedImage::from_bytes(&bytes).unwrap();
+        assert_eq!(image.format(), CompressedFormat::Bc3);
+        assert_eq!(image.levels().len(), 4);
+        assert_eq!(image.levels()[0].len(), 64);
+        assert_eq!(image.levels()[1], [4; 16]);
+        assert_eq!(image.levels()[3], [6; 16]);
+        bytes.pop();
+        assert_eq!(read_dds(&bytes).err(), Some(ImageError::InvalidData));
+    }
+
+    #[test]
+    fn dx10() {
+        let mut bytes = dds(b"DX10", 4, 4, 1);
+        for value in [99u32, 3, 0, 1, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0; 16]);
+        let image = read_dds(&bytes).unwrap();
+        assert_eq!((image.format(), image.is_srgb()), (CompressedFormat::Bc7, true));
+        assert_eq!(read_dds(&dds(b"RGBA", 4, 4, 1)).err(), Some(ImageError::Unsupported));
+    }
+
+    #[test]
+    fn huge_dimensions() {
+        let bytes = dds(b"DXT5", u32::MAX, u32::MAX, 1);
+        assert_eq!(read_dds(&bytes).err(), Some(ImageError::InvalidDimensions));
+    }
+}
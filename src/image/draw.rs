@@ -0,0 +1,308 @@
+use crate::color::{ColorBlend, ColorDescriptor};
+use crate::image::Image;
+use alloc::{vec, vec::Vec};
+
+/// The number of rows sampled per pixel row when filling polygons. Coverage along each sampled row
+/// is exact, so this only affects the quality of mostly horizontal edges.
+const POLYGON_SAMPLES: usize = 4;
+
+/// Drawing operations. Coordinates are in pixels, with (0, 0) at the top left corner of the top
+/// left pixel, so the center of pixel (x, y) is at (x + 0.5, y + 0.5). Shapes are anti-aliased by
+/// their coverage of each pixel, and blended through `ColorBlend`, so colors with alpha are drawn
+/// over the image. Parts of shapes outside of the image are clipped.
+impl<T: ColorBlend> Image<T> {
+    /// Blends a color into a single pixel with the given coverage, from 0 to 1. Pixels outside of
+    /// the image are ignored.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: T, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width() as i32 || y >= self.height() as i32 || coverage <= 0.0 {
+            return;
+        }
+        let index = self.index_for(x as u32, y as u32);
+        self.set_indexed(index, color.blend(self.get_indexed(index), coverage.min(1.0)));
+    }
+
+    /// Fills a rectangle with its top left pixel at (x, y).
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32, color: T) {
+        let right = (x as i64 + width as i64).min(self.width() as i64) as i32;
+        let bottom = (y as i64 + height as i64).min(self.height() as i64) as i32;
+        for py in y.max(0)..bottom {
+            for px in x.max(0)..right {
+                self.blend_pixel(px, py, color, 1.0);
+            }
+        }
+    }
+
+    /// Outlines a rectangle with its top left pixel at (x, y). The outline is drawn inside the
+    /// rectangle, `thickness` pixels wide.
+    pub fn stroke_rect(&mut self, x: i32, y: i32, width: u32, height: u32, thickness: u32, color: T) {
+        if thickness.saturating_mul(2) >= width || thickness.saturating_mul(2) >= height {
+            self.fill_rect(x, y, width, height, color);
+            return;
+        }
+        let (t, inner) = (thickness as i32, height - thickness * 2);
+        self.fill_rect(x, y, width, thickness, color);
+        self.fill_rect(x, y + height as i32 - t, width, thickness, color);
+        self.fill_rect(x, y + t, thickness, inner, color);
+        self.fill_rect(x + width as i32 - t, y + t, thickness, inner, color);
+    }
+
+    /// Draws a line between two points with round caps.
+    pub fn draw_line(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, thickness: f32, color: T) {
+        let half = thickness * 0.5;
+        let bounds = (x0.min(x1) - half, y0.min(y1) - half, x0.max(x1) + half, y0.max(y1) + half);
+        self.draw_distance(bounds, color, |x, y| segment_distance((x, y), (x0, y0), (x1, y1)) - half);
+    }
+
+    /// Fills a circle.
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, color: T) {
+        self.fill_ellipse(cx, cy, radius, radius, color);
+    }
+
+    /// Outlines a circle. The outline is centered on the circle's edge.
+    pub fn stroke_circle(&mut self, cx: f32, cy: f32, radius: f32, thickness: f32, color: T) {
+        self.stroke_ellipse(cx, cy, radius, radius, thickness, color);
+    }
+
+    /// Fills an axis aligned ellipse with the given horizontal and vertical radii.
+    pub fn fill_ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, color: T) {
+        let bounds = (cx - rx, cy - ry, cx + rx, cy + ry);
+        self.draw_distance(bounds, color, |x, y| ellipse_distance(x - cx, y - cy, rx, ry));
+    }
+
+    /// Outlines an axis aligned ellipse with the given horizontal and vertical radii. The outline
+    /// is centered on the ellipse's edge.
+    pub fn stroke_ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32, thickness: f32, color: T) {
+        let half = thickness * 0.5;
+        let bounds = (cx - rx - half, cy - ry - half, cx + rx + half, cy + ry + half);
+        self.draw_distance(bounds, color, |x, y| ellipse_distance(x - cx, y - cy, rx, ry).abs() - half);
+    }
+
+    /// Fills a polygon using the even-odd rule, so self intersecting polygons leave holes where
+    /// they overlap. The polygon is closed between its last and first points.
+    pub fn fill_polygon(&mut self, points: &[(f32, f32)], color: T) {
+        if points.len() < 3 {
+            return;
+        }
+        let top = points.iter().map(|point| point.1).fold(f32::MAX, f32::min).floor().max(0.0) as i32;
+        let bottom = points.iter().map(|point| point.1).fold(f32::MIN, f32::max).ceil();
+        let bottom = bottom.min(self.height() as f32) as i32;
+        let width = self.width() as usize;
+        let mut coverage = vec![0.0f32; width];
+        let mut crossings = Vec::new();
+        for row in top..bottom {
+            coverage.iter_mut().for_each(|coverage| *coverage = 0.0);
+            for sample in 0..POLYGON_SAMPLES {
+                let y = row as f32 + (sample as f32 + 0.5) / POLYGON_SAMPLES as f32;
+                crossings.clear();
+                for (index, a) in points.iter().enumerate() {
+                    let b = points[(index + 1) % points.len()];
+                    if (a.1 <= y) != (b.1 <= y) {
+                        crossings.push(a.0 + (y - a.1) / (b.1 - a.1) * (b.0 - a.0));
+                    }
+                }
+                crossings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+                for span in crossings.chunks_exact(2) {
+                    add_span(&mut coverage, span[0], span[1], 1.0 / POLYGON_SAMPLES as f32);
+                }
+            }
+            for (x, coverage) in coverage.iter().enumerate() {
+                self.blend_pixel(x as i32, row, color, *coverage);
+            }
+        }
+    }
+
+    /// Outlines a polygon. The outline is centered on the polygon's edges, and closed between its
+    /// last and first points.
+    pub fn stroke_polygon(&mut self, points: &[(f32, f32)], thickness: f32, color: T) {
+        if points.is_empty() {
+            return;
+        }
+        let half = thickness * 0.5;
+        let left = points.iter().map(|point| point.0).fold(f32::MAX, f32::min) - half;
+        let top = points.iter().map(|point| point.1).fold(f32::MAX, f32::min) - half;
+        let right = points.iter().map(|point| point.0).fold(f32::MIN, f32::max) + half;
+        let bottom = points.iter().map(|point| point.1).fold(f32::MIN, f32::max) + half;
+        self.draw_distance((left, top, right, bottom), color, |x, y| {
+            let mut distance = f32::MAX;
+            for (index, &a) in points.iter().enumerate() {
+                let b = points[(index + 1) % points.len()];
+                distance = distance.min(segment_distance((x, y), a, b));
+            }
+            distance - half
+        });
+    }
+
+    /// Blends every pixel near the bounds by the coverage implied by a signed distance function,
+    /// which is negative inside the shape.
+    fn draw_distance(&mut self, bounds: (f32, f32, f32, f32), color: T, distance: impl Fn(f32, f32) -> f32) {
+        let (left, top, right, bottom) = bounds;
+        let left = (left - 1.0).floor().max(0.0) as i32;
+        let top = (top - 1.0).floor().max(0.0) as i32;
+        let right = (right + 1.0).ceil().min(self.width() as f32) as i32;
+        let bottom = (bottom + 1.0).ceil().min(self.height() as f32) as i32;
+        for y in top..bottom {
+            for x in left..right {
+                let coverage = (0.5 - distance(x as f32 + 0.5, y as f32 + 0.5)).clamp(0.0, 1.0);
+                self.blend_pixel(x, y, color, coverage);
+            }
+        }
+    }
+}
+
+impl<T: ColorDescriptor + PartialEq> Image<T> {
+    /// Replaces the color of the region of same colored pixels connected to (x, y), including
+    /// (x, y) itself. Pixels are connected horizontally and vertically. The color replaces pixels
+    /// outright instead of blending.
+    pub fn flood_fill(&mut self, x: u32, y: u32, color: T) {
+        let target = self.get(x, y);
+        if target == color {
+            return;
+        }
+        let (width, height) = (self.width(), self.height());
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            if self.get(x, y) != target {
+                continue;
+            }
+            let mut left = x;
+            while left > 0 && self.get(left - 1, y) == target {
+                left -= 1;
+            }
+            let mut right = x;
+            while right + 1 < width && self.get(right + 1, y) == target {
+                right += 1;
+            }
+            for x in left..=right {
+                self.set(x, y, color);
+            }
+            // Only the first pixel of each run above and below is pushed, the rest of the run is
+            // found when it's popped.
+            for row in [y.checked_sub(1), Some(y + 1).filter(|&row| row < height)].iter().flatten() {
+                let mut in_run = false;
+                for x in left..=right {
+                    let matches = self.get(x, *row) == target;
+                    if matches && !in_run {
+                        stack.push((x, *row));
+                    }
+                    in_run = matches;
+                }
+            }
+        }
+    }
+}
+
+/// The distance from a point to a line segment.
+fn segment_distance(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let (px, py) = (point.0 - a.0, point.1 - a.1);
+    let length = dx * dx + dy * dy;
+    let t = if length > 0.0 {
+        ((px * dx + py * dy) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (ex, ey) = (px - dx * t, py - dy * t);
+    (ex * ex + ey * ey).sqrt()
+}
+
+/// Approximates the signed distance from a point, relative to the center, to the edge of an
+/// ellipse, by dividing the implicit function by its gradient. Exact for circles.
+fn ellipse_distance(x: f32, y: f32, rx: f32, ry: f32) -> f32 {
+    if rx <= 0.0 || ry <= 0.0 {
+        return f32::MAX;
+    }
+    let k = ((x / rx) * (x / rx) + (y / ry) * (y / ry)).sqrt();
+    let gradient = ((x / (rx * rx)) * (x / (rx * rx)) + (y / (ry * ry)) * (y / (ry * ry))).sqrt();
+    if gradient == 0.0 {
+        -rx.min(ry)
+    } else {
+        k * (k - 1.0) / gradient
+    }
+}
+
+/// Adds the horizontal coverage of the span from x0 to x1 to each pixel it overlaps.
+fn add_span(coverage: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let x0 = x0.max(0.0);
+    let x1 = x1.min(coverage.len() as f32);
+    if x1 <= x0 {
+        return;
+    }
+    let (first, last) = (x0 as usize, x1 as usize);
+    if first == last {
+        coverage[first] += (x1 - x0) * weight;
+        return;
+    }
+    coverage[first] += (first as f32 + 1.0 - x0) * weight;
+    for coverage in &mut coverage[first + 1..last] {
+        *coverage += weight;
+    }
+    if last < coverage.len() {
+        coverage[last] += (x1 - last as f32) * weight;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{R8, RGBA8};
+
+    fn total(image: &Image<R8>) -> u32 {
+        image.as_slice().iter().map(|pixel| pixel.r as u32).sum()
+    }
+
+    #[test]
+    fn shapes() {
+        let mut image = Image::from_color(R8::new(0), 8, 8);
+        image.fill_rect(-2, 6, 4, 10, R8::new(255));
+        assert_eq!(total(&image), 255 * 4);
+
+        let mut image = Image::from_color(R8::new(0), 8, 8);
+        image.stroke_rect(0, 0, 8, 8, 1, R8::new(255));
+        assert_eq!(total(&image), 255 * 28);
+        assert_eq!(image.get(4, 4), R8::new(0));
+        image.stroke_rect(0, 0, 8, 8, u32::MAX, R8::new(255));
+        assert_eq!(total(&image), 255 * 64);
+
+        // A 4x4 square as a polygon covers exactly 16 pixels, and a triangle half of that.
+        let mut image = Image::from_color(R8::new(0), 8, 8);
+        image.fill_polygon(&[(2.0, 2.0), (6.0, 2.0), (6.0, 6.0), (2.0, 6.0)], R8::new(255));
+        assert_eq!(total(&image), 255 * 16);
+        let mut image = Image::from_color(R8::new(0), 8, 8);
+        image.fill_polygon(&[(2.0, 2.0), (6.0, 2.0), (6.0, 6.0)], R8::new(255));
+        assert!((total(&image) as i32 - 255 * 8).abs() < 255);
+
+        // A circle's area is close to pi r squared.
+        let mut image = Image::from_color(R8::new(0), 16, 16);
+        image.fill_circle(8.0, 8.0, 5.0, R8::new(255));
+        let area = total(&image) as f32 / 255.0;
+        assert!((area - core::f32::consts::PI * 25.0).abs() < 1.0);
+        assert_eq!(image.get(8, 8), R8::new(255));
+        assert_eq!(image.get(0, 0), R8::new(0));
+
+        // A horizontal line on pixel centers is fully covered along its length.
+        let mut image = Image::from_color(R8::new(0), 8, 8);
+        image.draw_line(1.5, 3.5, 5.5, 3.5, 1.0, R8::new(255));
+        assert_eq!(image.get(3, 3), R8::new(255));
+        assert_eq!(image.get(3, 2), R8::new(0));
+    }
+
+    #[test]
+    fn blending_and_flood_fill() {
+        let mut image = Image::from_color(RGBA8::RED, 4, 4);
+        image.fill_rect(0, 0, 2, 4, RGBA8::new(0, 0, 255, 128));
+        assert_eq!(image.get(0, 0), RGBA8::new(127, 0, 128, 255));
+
+        // Fill the left half, leaving the right half untouched.
+        image.flood_fill(1, 2, RGBA8::GREEN);
+        assert_eq!(image.get(0, 3), RGBA8::GREEN);
+        assert_eq!(image.get(2, 0), RGBA8::RED);
+
+        // A comb, reachable only through the gaps between the teeth.
+        let mut image = Image::from_color(R8::new(0), 9, 5);
+        for x in (1..9).step_by(2) {
+            image.fill_rect(x, 0, 1, 4, R8::new(1));
+        }
+        image.flood_fill(0, 0, R8::new(2));
+        assert_eq!(image.as_slice().iter().filter(|pixel| pixel.r == 2).count(), 9 * 5 - 4 * 4);
+    }
+}
@@ -0,0 +1,15 @@
+/// A list specifying the ways decoding or encoding an image can fail.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ImageError {
+    /// The bytes don't match the signature of any supported format.
+    UnknownFormat,
+    /// The image uses a feature of its format that isn't supported, such as RLE compressed BMPs.
+    Unsupported,
+    /// The image data is malformed or truncated.
+    InvalidData,
+    /// The image has a width or height of 0, or has more pixels than an image can address.
+    InvalidDimensions,
+    /// The images being packed don't fit in the requested size.
+    OutOfSpace,
+}
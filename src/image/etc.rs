@@ -0,0 +1,212 @@
+use crate::color::RGBA8;
+use core::convert::TryInto;
+
+/// ETC1 intensity modifiers, indexed by table codeword then pixel index.
+const MODIFIERS: [[i32; 4]; 8] = [
+    [2, 8, -2, -8],
+    [5, 17, -5, -17],
+    [9, 29, -9, -29],
+    [13, 42, -13, -42],
+    [18, 60, -18, -60],
+    [24, 80, -24, -80],
+    [33, 106, -33, -106],
+    [47, 183, -47, -183],
+];
+
+/// T and H mode distances.
+const DISTANCES: [i32; 8] = [3, 6, 11, 16, 23, 32, 41, 64];
+
+/// EAC alpha modifiers, indexed by table then pixel index.
+const EAC_MODIFIERS: [[i32; 8]; 16] = [
+    [-3, -6, -9, -15, 2, 5, 8, 14],
+    [-3, -7, -10, -13, 2, 6, 9, 12],
+    [-2, -5, -8, -13, 1, 4, 7, 12],
+    [-2, -4, -6, -13, 1, 3, 5, 12],
+    [-3, -6, -8, -12, 2, 5, 7, 11],
+    [-3, -7, -9, -11, 2, 6, 8, 10],
+    [-4, -7, -8, -11, 3, 6, 7, 10],
+    [-3, -5, -8, -11, 2, 4, 7, 10],
+    [-2, -6, -8, -10, 1, 5, 7, 9],
+    [-2, -5, -8, -10, 1, 4, 7, 9],
+    [-2, -4, -8, -10, 1, 3, 7, 9],
+    [-2, -5, -7, -10, 1, 4, 6, 9],
+    [-3, -4, -7, -10, 2, 3, 6, 9],
+    [-1, -2, -3, -10, 0, 1, 2, 9],
+    [-4, -6, -8, -9, 3, 5, 7, 8],
+    [-3, -5, -7, -9, 2, 4, 6, 8],
+];
+
+fn bits(block: u64, high: u32, low: u32) -> i32 {
+    ((block >> low) & ((1 << (high - low + 1)) - 1)) as i32
+}
+
+fn clamp(value: i32) -> u8 {
+    value.clamp(0, 255) as u8
+}
+
+fn extend(value: i32, bits: u32) -> i32 {
+    (value << (8 - bits)) | (value >> (2 * bits - 8))
+}
+
+fn offset([r, g, b]: [i32; 3], amount: i32) -> RGBA8 {
+    RGBA8::new(clamp(r + amount), clamp(g + amount), clamp(b + amount), 255)
+}
+
+/// Decodes an ETC2 RGB block into 4x4 pixels in row order. ETC1 blocks decode identically. With
+/// punchthrough alpha, bit 33 marks the block as opaque instead of selecting differential mode,
+/// and blocks that aren't opaque use one pixel index for transparency.
+pub(crate) fn decode_etc2_rgb(block: &[u8], punchthrough: bool) -> [RGBA8; 16] {
+    let block = u64::from_be_bytes(block[..8].try_into().unwrap());
+    let opaque = !punchthrough || bits(block, 33, 33) == 1;
+    let differential = punchthrough || bits(block, 33, 33) == 1;
+    // Pixel indices are stored column by column, with the high and low bits split in two halves.
+    let index = |i: usize| {
+        let shift = (i % 4) * 4 + i / 4;
+        ((bits(block, 16 + shift as u32, 16 + shift as u32) << 1) | bits(block, shift as u32, shift as u32))
+            as usize
+    };
+
+    let (r, g, b) = (bits(block, 63, 59), bits(block, 55, 51), bits(block, 47, 43));
+    let (dr, dg, db) =
+        (bits(block, 58, 56) << 29 >> 29, bits(block, 50, 48) << 29 >> 29, bits(block, 42, 40) << 29 >> 29);
+    if differential && !(0..32).contains(&(r + dr)) {
+        // T mode.
+        let base = [
+            extend((bits(block, 60, 59) << 2) | bits(block, 57, 56), 4),
+            extend(bits(block, 55, 52), 4),
+            extend(bits(block, 51, 48), 4),
+        ];
+        let other =
+            [extend(bits(block, 47, 44), 4), extend(bits(block, 43, 40), 4), extend(bits(block, 39, 36), 4)];
+        let distance = DISTANCES[((bits(block, 35, 34) << 1) | bits(block, 32, 32)) as usize];
+        let paints = [offset(base, 0), offset(other, distance), offset(other, 0), offset(other, -distance)];
+        return paint(paints, opaque, index);
+    }
+    if differential && !(0..32).contains(&(g + dg)) {
+        // H mode.
+        let base = [
+            bits(block, 62, 59),
+            (bits(block, 58, 56) << 1) | bits(block, 52, 52),
+            (bits(block, 51, 51) << 3) | bits(block, 49, 47),
+        ];
+        let other = [bits(block, 46, 43), bits(block, 42, 39), bits(block, 38, 35)];
+        let value = |[r, g, b]: [i32; 3]| (r << 8) | (g << 4) | b;
+        let ordered = (value(base) >= value(other)) as i32;
+        let distance =
+            DISTANCES[((bits(block, 34, 34) << 2) | (bits(block, 32, 32) << 1) | ordered) as usize];
+        let (base, other) = (base.map(|c| extend(c, 4)), other.map(|c| extend(c, 4)));
+        let paints = [
+            offset(base, distance),
+            offset(base, -distance),
+            offset(other, distance),
+            offset(other, -distance),
+        ];
+        return paint(paints, opaque, index);
+    }
+    if differential && !(0..32).contains(&(b + db)) {
+        // Planar mode, which is always opaque.
+        let origin = [
+            extend(bits(block, 62, 57), 6),
+            extend((bits(block, 56, 56) << 6) | bits(block, 54, 49), 7),
+            extend((bits(block, 48, 48) << 5) | (bits(block, 44, 43) << 3) | bits(block, 41, 39), 6),
+        ];
+        let horizontal = [
+            extend((bits(block, 38, 34) << 1) | bits(block, 32, 32), 6),
+            extend(bits(block, 31, 25), 7),
+            extend(bits(block, 24, 19), 6),
+        ];
+        let vertical =
+            [extend(bits(block, 18, 13), 6), extend(bits(block, 12, 6), 7), extend(bits(block, 5, 0), 6)];
+        return core::array::from_fn(|i| {
+            let (x, y) = ((i % 4) as i32, (i / 4) as i32);
+            let channel = |c: usize| {
+                clamp(
+                    (x * (horizontal[c] - origin[c]) + y * (vertical[c] - origin[c]) + 4 * origin[c] + 2)
+                        >> 2,
+                )
+            };
+            RGBA8::new(channel(0), channel(1), channel(2), 255)
+        });
+    }
+
+    let (first, second) = if differential {
+        ([r, g, b].map(|c| extend(c, 5)), [r + dr, g + dg, b + db].map(|c| extend(c, 5)))
+    } else {
+        (
+            [bits(block, 63, 60), bits(block, 55, 52), bits(block, 47, 44)].map(|c| extend(c, 4)),
+            [bits(block, 59, 56), bits(block, 51, 48), bits(block, 43, 40)].map(|c| extend(c, 4)),
+        )
+    };
+    let tables = [bits(block, 39, 37) as usize, bits(block, 36, 34) as usize];
+    let flip = bits(block, 32, 32) == 1;
+    core::array::from_fn(|i| {
+        let (x, y) = (i % 4, i / 4);
+        let subblock = if flip {
+            y / 2
+        } else {
+            x / 2
+        };
+        let index = index(i);
+        if !opaque && index == 2 {
+            return RGBA8::TRANSPARENT;
+        }
+        let modifier = if !opaque && index == 0 {
+            0
+        } else {
+            MODIFIERS[tables[subblock]][index]
+        };
+        offset([first, second][subblock], modifier)
+    })
+}
+
+/// Maps the pixels of a T or H mode block to their paint colors.
+fn paint(paints: [RGBA8; 4], opaque: bool, index: impl Fn(usize) -> usize) -> [RGBA8; 16] {
+    core::array::from_fn(|i| match index(i) {
+        2 if !opaque => RGBA8::TRANSPARENT,
+        index => paints[index],
+    })
+}
+
+/// Decodes an ETC2 RGBA block, an EAC alpha block followed by an ETC2 RGB block, into 4x4 pixels in
+/// row order.
+pub(crate) fn decode_etc2_rgba(block: &[u8]) -> [RGBA8; 16] {
+    let mut pixels = decode_etc2_rgb(&block[8..], false);
+    let alpha = u64::from_be_bytes(block[..8].try_into().unwrap());
+    let base = bits(alpha, 63, 56);
+    let multiplier = bits(alpha, 55, 52);
+    let table = EAC_MODIFIERS[bits(alpha, 51, 48) as usize];
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        let shift = 45 - 3 * ((i % 4) * 4 + i / 4) as u32;
+        pixel.a = clamp(base + table[bits(alpha, shift + 2, shift) as usize] * multiplier);
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etc1_individual() {
+        // Left half red with table 0, right half blue with table 7. Pixel (1, 0) uses index 1 and
+        // pixel (2, 3) uses index 3.
+        let mut block = [0xf0, 0x00, 0x0f, 0b000_111_0_0, 0, 0, 0, 0];
+        block[4] = 1 << 3; // High bit of pixel (2, 3) is bit 27.
+        block[6] = 1 << 3; // Low bit of pixel (2, 3) is bit 11.
+        block[7] = 1 << 4; // Low bit of pixel (1, 0) is bit 4.
+        let pixels = decode_etc2_rgb(&block, false);
+        assert_eq!(pixels[0], RGBA8::new(255, 2, 2, 255));
+        assert_eq!(pixels[1], RGBA8::new(255, 8, 8, 255));
+        assert_eq!(pixels[14], RGBA8::new(0, 0, 72, 255));
+    }
+
+    #[test]
+    fn eac_alpha() {
+        // Base 128, multiplier 2, table 13, every pixel at index 7 (+9) except the first at 3 (-10).
+        let mut block = [128, 0x2d, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0];
+        block[2] = 0b0111_1111;
+        let pixels = decode_etc2_rgba(&block);
+        assert_eq!(pixels[0].a, 108);
+        assert_eq!(pixels[1].a, 146);
+    }
+}
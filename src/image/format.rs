@@ -0,0 +1,173 @@
+use super::{Image, ImageError};
+use crate::color::{ColorComponentType, ColorDescriptor, ColorLayoutFormat, RGBA8};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+/// The file formats images can be decoded from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Bmp,
+    Tga,
+    Qoi,
+    Gif,
+    Ktx2,
+    Dds,
+}
+
+impl ImageFormat {
+    /// Identifies the format of an encoded image from its leading bytes. TGA has no signature, so
+    /// it's only reported if the bytes start with a plausible TGA header.
+    pub fn sniff(bytes: &[u8]) -> Option<ImageFormat> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some(ImageFormat::Jpeg)
+        } else if bytes.starts_with(b"BM") {
+            Some(ImageFormat::Bmp)
+        } else if bytes.starts_with(b"qoif") {
+            Some(ImageFormat::Qoi)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else if super::ktx2::is_ktx2(bytes) {
+            Some(ImageFormat::Ktx2)
+        } else if super::dds::is_dds(bytes) {
+            Some(ImageFormat::Dds)
+        } else if super::tga::is_tga(bytes) {
+            Some(ImageFormat::Tga)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decodes an image in any supported format into an RGBA image. Only the full size level of a
+/// compressed KTX2 or DDS image is decoded.
+pub fn read_image(bytes: &[u8]) -> Result<Image<RGBA8>, ImageError> {
+    match ImageFormat::sniff(bytes).ok_or(ImageError::UnknownFormat)? {
+        ImageFormat::Png => super::png::read_png_as(bytes),
+        ImageFormat::Jpeg => super::jpeg::read_jpeg(bytes),
+        ImageFormat::Bmp => super::bmp::read_bmp(bytes),
+        ImageFormat::Tga => super::tga::read_tga(bytes),
+        ImageFormat::Qoi => super::qoi::read_qoi(bytes),
+        ImageFormat::Gif => super::gif::read_gif(bytes),
+        ImageFormat::Ktx2 | ImageFormat::Dds => {
+            let image = super::CompressedImage::from_bytes(bytes)?;
+            image.decode()?.into_iter().next().ok_or(ImageError::InvalidData)
+        }
+    }
+}
+
+/// Creates an image from decoded pixels, checking the dimensions are usable.
+pub(crate) fn checked_image(pixels: Vec<RGBA8>, width: u32, height: u32) -> Result<Image<RGBA8>, ImageError> {
+    check_dimensions(width, height)?;
+    if pixels.len() != (width * height) as usize {
+        return Err(ImageError::InvalidData);
+    }
+    Ok(Image::from_vec(pixels, width, height))
+}
+
+/// Gets the bytes of an image for encoding along with their layout. Only images with 8 bit
+/// components can be encoded, and `BGRA` is reordered to `RGBA`.
+pub(crate) fn encodable_bytes<T: ColorDescriptor>(
+    image: &Image<T>,
+) -> Result<(ColorLayoutFormat, Cow<'_, [u8]>), ImageError> {
+    if T::component_type() != ColorComponentType::U8 {
+        return Err(ImageError::Unsupported);
+    }
+    let bytes = image.as_bytes();
+    match T::layout() {
+        ColorLayoutFormat::BGRA => {
+            let rgba = bytes.chunks_exact(4).flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]]).collect();
+            Ok((ColorLayoutFormat::RGBA, Cow::Owned(rgba)))
+        }
+        layout => Ok((layout, Cow::Borrowed(bytes))),
+    }
+}
+
+/// The most pixels a decoded image may have, the area of a 16384x16384 image. Larger headers are
+/// rejected before anything is allocated for them.
+const MAX_PIXELS: u64 = 1 << 28;
+
+/// Checks that an image of the given size can be created.
+pub(crate) fn check_dimensions(width: u32, height: u32) -> Result<(), ImageError> {
+    if width == 0 || height == 0 || width as u64 * height as u64 > MAX_PIXELS {
+        return Err(ImageError::InvalidDimensions);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff() {
+        assert_eq!(ImageFormat::sniff(b"\x89PNG\r\n\x1a\n...."), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::sniff(&[0xff, 0xd8, 0xff, 0xe0]), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::sniff(b"GIF89a"), Some(ImageFormat::Gif));
+        assert_eq!(ImageFormat::sniff(b"qoif"), Some(ImageFormat::Qoi));
+        assert_eq!(ImageFormat::sniff(b"not an image at all"), None);
+        assert_eq!(read_image(b"nope").err(), Some(ImageError::UnknownFormat));
+    }
+
+    #[test]
+    fn bmp_bottom_up_24() {
+        // 2x2, 24 bits per pixel, rows padded to 4 bytes and stored bottom to top.
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"BM");
+        bytes.extend_from_slice(&(14u32 + 40 + 16).to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&54u32.to_le_bytes());
+        bytes.extend_from_slice(&40u32.to_le_bytes());
+        bytes.extend_from_slice(&2i32.to_le_bytes());
+        bytes.extend_from_slice(&2i32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&24u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 24]);
+        bytes.extend_from_slice(&[255, 0, 0, 0, 255, 0, 0, 0]); // Bottom row: blue, green.
+        bytes.extend_from_slice(&[0, 0, 255, 255, 255, 255, 0, 0]); // Top row: red, white.
+        let image = read_image(&bytes).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::RED, RGBA8::WHITE, RGBA8::BLUE, RGBA8::GREEN]);
+    }
+
+    #[test]
+    fn tga_rle_top_left() {
+        // 3x1, RLE true color, 32 bits per pixel, top left origin.
+        let mut bytes = alloc::vec![0u8, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 1, 0, 32, 0x28];
+        bytes.extend_from_slice(&[0x81, 0, 0, 255, 255]); // Two red pixels.
+        bytes.extend_from_slice(&[0x00, 255, 0, 0, 128]); // One translucent blue pixel.
+        assert_eq!(ImageFormat::sniff(&bytes), Some(ImageFormat::Tga));
+        let image = read_image(&bytes).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::RED, RGBA8::RED, RGBA8::new(0, 0, 255, 128)]);
+    }
+
+    #[test]
+    fn encode_roundtrip() {
+        use crate::color::{R8, RGB8};
+        let rgba = Image::from_vec(alloc::vec![RGBA8::RED, RGBA8::new(1, 2, 3, 4)], 2, 1);
+        assert_eq!(read_image(&rgba.to_png().unwrap()).unwrap().as_slice(), rgba.as_slice());
+        assert_eq!(read_image(&rgba.to_qoi().unwrap()).unwrap().as_slice(), rgba.as_slice());
+
+        let rgb = Image::from_vec(alloc::vec![RGB8::new(10, 20, 30)], 1, 1);
+        assert_eq!(read_image(&rgb.to_png().unwrap()).unwrap().as_slice(), [RGBA8::new(10, 20, 30, 255)]);
+
+        let gray = Image::from_vec(alloc::vec![R8::new(7)], 1, 1);
+        assert_eq!(read_image(&gray.to_qoi().unwrap()).unwrap().as_slice(), [RGBA8::new(7, 7, 7, 255)]);
+        assert_eq!(read_image(&gray.to_png().unwrap()).unwrap().as_slice(), [RGBA8::new(7, 7, 7, 255)]);
+    }
+
+    #[test]
+    fn png_preserves_layout() {
+        use crate::color::{R8, RG8};
+        let gray = Image::from_vec(alloc::vec![R8::new(7), R8::new(200)], 2, 1);
+        let decoded = Image::<R8>::try_from_png(&gray.to_png().unwrap()).unwrap();
+        assert_eq!(decoded.as_slice(), gray.as_slice());
+
+        let rgba = Image::from_vec(alloc::vec![RGBA8::new(100, 100, 100, 50)], 1, 1);
+        let decoded = Image::<RG8>::try_from_png(&rgba.to_png().unwrap()).unwrap();
+        assert_eq!(decoded.as_slice(), [RG8::new(100, 50)]);
+        assert_eq!(Image::<R8>::try_from_png(b"nope").err(), Some(ImageError::InvalidData));
+    }
+}
@@ -0,0 +1,84 @@
+use super::animation::AnimationFrame;
+use super::{Image, ImageError};
+use crate::color::RGBA8;
+use alloc::vec::Vec;
+use core::time::Duration;
+use gif::{ColorOutput, DecodeOptions, DisposalMethod};
+
+/// Decodes the first frame of a GIF, placed on a transparent canvas the size of the logical screen.
+pub fn read_gif(bytes: &[u8]) -> Result<Image<RGBA8>, ImageError> {
+    let mut options = DecodeOptions::new();
+    options.set_color_output(ColorOutput::RGBA);
+    let mut decoder = options.read_info(bytes).map_err(|_| ImageError::InvalidData)?;
+    let (width, height) = (decoder.width() as u32, decoder.height() as u32);
+    super::format::check_dimensions(width, height)?;
+
+    let mut canvas = Image::from_color(RGBA8::TRANSPARENT, width, height);
+    let frame =
+        decoder.read_next_frame().map_err(|_| ImageError::InvalidData)?.ok_or(ImageError::InvalidData)?;
+    draw_frame(&mut canvas, frame);
+    Ok(canvas)
+}
+
+/// Decodes every frame of a GIF. Each frame is drawn over the previous ones, after the previous
+/// frame's disposal is applied. Background disposal clears to transparent.
+pub(crate) fn read_gif_frames(bytes: &[u8]) -> Result<Vec<AnimationFrame>, ImageError> {
+    let mut options = DecodeOptions::new();
+    options.set_color_output(ColorOutput::RGBA);
+    let mut decoder = options.read_info(bytes).map_err(|_| ImageError::InvalidData)?;
+    let (width, height) = (decoder.width() as u32, decoder.height() as u32);
+    super::format::check_dimensions(width, height)?;
+
+    let mut canvas = Image::from_color(RGBA8::TRANSPARENT, width, height);
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().map_err(|_| ImageError::InvalidData)? {
+        let previous = match frame.dispose {
+            DisposalMethod::Previous => Some(canvas.clone()),
+            _ => None,
+        };
+        draw_frame(&mut canvas, frame);
+        frames.push(AnimationFrame {
+            image: canvas.clone(),
+            delay: Duration::from_millis(frame.delay as u64 * 10),
+        });
+
+        match (frame.dispose, previous) {
+            (DisposalMethod::Previous, Some(previous)) => canvas = previous,
+            (DisposalMethod::Background, _) => {
+                let right = (frame.left as u32 + frame.width as u32).min(width);
+                let bottom = (frame.top as u32 + frame.height as u32).min(height);
+                for y in frame.top as u32..bottom {
+                    for x in frame.left as u32..right {
+                        canvas.set(x, y, RGBA8::TRANSPARENT);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if frames.is_empty() {
+        return Err(ImageError::InvalidData);
+    }
+    Ok(frames)
+}
+
+/// Draws a decoded frame onto the canvas at its offset. Transparent pixels leave the canvas
+/// untouched, and parts of the frame outside of the canvas are clipped.
+pub(crate) fn draw_frame(canvas: &mut Image<RGBA8>, frame: &gif::Frame) {
+    let (left, top) = (frame.left as u32, frame.top as u32);
+    for y in 0..frame.height as u32 {
+        for x in 0..frame.width as u32 {
+            if left + x >= canvas.width() || top + y >= canvas.height() {
+                continue;
+            }
+            let index = ((y * frame.width as u32 + x) * 4) as usize;
+            let rgba = match frame.buffer.get(index..index + 4) {
+                Some(rgba) => rgba,
+                None => return,
+            };
+            if rgba[3] != 0 {
+                canvas.set(left + x, top + y, RGBA8::new(rgba[0], rgba[1], rgba[2], rgba[3]));
+            }
+        }
+    }
+}
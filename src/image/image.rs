@@ -1,4 +1,5 @@
 use crate::color::{ColorDescriptor, RGBA8};
+use crate::image::{AnimationFrame, ImageError};
 use alloc::{vec, vec::Vec};
 
 /// Basic image type.
@@ -14,9 +15,40 @@ impl Image<RGBA8> {
     pub fn from_png(bytes: &[u8]) -> Image<RGBA8> {
         crate::image::png::read_png(bytes)
     }
+
+    /// Interpret a slice of bytes as an encoded image and decodes it into an RGBA image. The format
+    /// is detected from the bytes, and may be PNG, JPEG, BMP, TGA, QOI, GIF, KTX2, or DDS. Only the
+    /// first frame of an animated GIF, and the full size level of a compressed image, is decoded.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Image<RGBA8>, ImageError> {
+        crate::image::format::read_image(bytes)
+    }
+
+    /// Interpret a slice of bytes as an animated GIF or PNG and decodes every frame, with its delay.
+    /// Frame disposal and blending are applied, so each frame is the complete image to show. Other
+    /// formats decode to a single frame with no delay.
+    pub fn frames_from_bytes(bytes: &[u8]) -> Result<Vec<AnimationFrame>, ImageError> {
+        crate::image::animation::read_frames(bytes)
+    }
+}
+
+/// The size of a pixel of the color in bytes.
+fn pixel_size<T: ColorDescriptor>() -> usize {
+    let size = T::layout().components() * T::component_type().size();
+    debug_assert_eq!(core::mem::size_of::<T>(), size, "Color type's size doesn't match its ColorDescriptor.");
+    size
 }
 
 impl<T: ColorDescriptor> Image<T> {
+    /// Interpret a slice of bytes as a PNG and decodes it into an image of the requested color
+    /// type, keeping as much of the source format as the type allows. Grayscale can be decoded to
+    /// `R8`, and grayscale with alpha to `RG8`, without expanding to RGBA. 16 bit images are reduced
+    /// to 8 bits, and palettes, including their tRNS transparency, are expanded. Color images decoded
+    /// to a single or two channel type are converted to luma. Only colors with `U8` components are
+    /// supported.
+    pub fn try_from_png(bytes: &[u8]) -> Result<Image<T>, ImageError> {
+        crate::image::png::read_png_as(bytes)
+    }
+
     /// Creates an image with the given color and size.
     pub fn from_color(color: T, width: u32, height: u32) -> Image<T> {
         assert!(width > 0 && height > 0, "Neither width or height can be 0.");
@@ -63,6 +95,44 @@ impl<T: ColorDescriptor> Image<T> {
         self.pixels.as_mut_slice()
     }
 
+    /// Gets the raw bytes of the image data, laid out as described by the color's
+    /// `ColorDescriptor`.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self.pixels.len() * pixel_size::<T>();
+        // Sound because `ColorDescriptor` implementors guarantee they're exactly their described
+        // components, with no padding.
+        unsafe { core::slice::from_raw_parts(self.pixels.as_ptr() as *const u8, len) }
+    }
+
+    /// Creates an image from raw bytes laid out as described by the color's `ColorDescriptor`.
+    /// Returns None if the byte length doesn't match the dimensions.
+    pub(crate) fn from_raw_bytes(bytes: &[u8], width: u32, height: u32) -> Option<Image<T>> {
+        let count = (width * height) as usize;
+        if bytes.len() != count * pixel_size::<T>() {
+            return None;
+        }
+        let mut pixels = vec![T::default(); count];
+        // Sound because `ColorDescriptor` implementors guarantee every bit pattern is valid.
+        unsafe {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), pixels.as_mut_ptr() as *mut u8, bytes.len());
+        }
+        Some(Image::from_vec(pixels, width, height))
+    }
+
+    /// Encodes the image as a PNG. The layout maps to the PNG color type: `R` is grayscale, `RG` is
+    /// grayscale with alpha, and `RGB` and `RGBA` are stored as is. `BGRA` is reordered to `RGBA`.
+    /// Only colors with `U8` components can be encoded.
+    pub fn to_png(&self) -> Result<Vec<u8>, ImageError> {
+        crate::image::png::write_png(self)
+    }
+
+    /// Encodes the image as a QOI. QOI only stores RGB and RGBA, so `R` is stored as gray RGB, and
+    /// `RG` as gray RGBA with the second component as alpha. `BGRA` is reordered to `RGBA`. Only
+    /// colors with `U8` components can be encoded.
+    pub fn to_qoi(&self) -> Result<Vec<u8>, ImageError> {
+        crate::image::qoi::write_qoi(self)
+    }
+
     /// Returns the underlying Vec that backs the image data.
     pub fn into_vec(self) -> Vec<T> {
         self.pixels
@@ -0,0 +1,38 @@
+use super::format::checked_image;
+use super::{Image, ImageError};
+use crate::color::RGBA8;
+use alloc::vec::Vec;
+use jpeg_decoder::{Decoder, PixelFormat};
+
+/// Decodes a baseline or progressive JPEG.
+pub fn read_jpeg(bytes: &[u8]) -> Result<Image<RGBA8>, ImageError> {
+    let mut decoder = Decoder::new(bytes);
+    let input = decoder.decode().map_err(|error| match error {
+        jpeg_decoder::Error::Unsupported(_) => ImageError::Unsupported,
+        _ => ImageError::InvalidData,
+    })?;
+    let info = decoder.info().ok_or(ImageError::InvalidData)?;
+
+    let output: Vec<RGBA8> = match info.pixel_format {
+        PixelFormat::L8 => input.iter().map(|&l| RGBA8::new(l, l, l, 255)).collect(),
+        PixelFormat::L16 => input
+            .chunks_exact(2)
+            .map(|l| {
+                let l = (u16::from_ne_bytes([l[0], l[1]]) >> 8) as u8;
+                RGBA8::new(l, l, l, 255)
+            })
+            .collect(),
+        PixelFormat::RGB24 => {
+            input.chunks_exact(3).map(|rgb| RGBA8::new(rgb[0], rgb[1], rgb[2], 255)).collect()
+        }
+        PixelFormat::CMYK32 => input
+            .chunks_exact(4)
+            .map(|cmyk| {
+                let k = 255 - cmyk[3] as u32;
+                let channel = |c: u8| ((255 - c as u32) * k / 255) as u8;
+                RGBA8::new(channel(cmyk[0]), channel(cmyk[1]), channel(cmyk[2]), 255)
+            })
+            .collect(),
+    };
+    checked_image(output, info.width as u32, info.height as u32)
+}
@@ -0,0 +1,144 @@
+use super::compressed::{CompressedFormat, CompressedImage};
+use super::ImageError;
+use alloc::vec::Vec;
+use core::convert::{TryFrom, TryInto};
+
+const IDENTIFIER: [u8; 12] = [0xab, b'K', b'T', b'X', b' ', b'2', b'0', 0xbb, b'\r', b'\n', 0x1a, b'\n'];
+const HEADER_SIZE: usize = 80;
+const LEVEL_INDEX_SIZE: usize = 24;
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, ImageError> {
+    let bytes = bytes.get(offset..offset + 4).ok_or(ImageError::InvalidData)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> Result<usize, ImageError> {
+    let bytes = bytes.get(offset..offset + 8).ok_or(ImageError::InvalidData)?;
+    let value = u64::from_le_bytes(bytes.try_into().unwrap());
+    usize::try_from(value).map_err(|_| ImageError::InvalidData)
+}
+
+/// Returns true if the bytes start with the KTX2 identifier.
+pub(crate) fn is_ktx2(bytes: &[u8]) -> bool {
+    bytes.starts_with(&IDENTIFIER)
+}
+
+/// Maps a Vulkan format to a compressed format, and whether it's sRGB encoded.
+fn format(vk_format: u32) -> Option<(CompressedFormat, bool)> {
+    let format = match vk_format {
+        131..=134 => CompressedFormat::Bc1,
+        135 | 136 => CompressedFormat::Bc2,
+        137 | 138 => CompressedFormat::Bc3,
+        139 => CompressedFormat::Bc4,
+        141 => CompressedFormat::Bc5,
+        143 => CompressedFormat::Bc6h,
+        145 | 146 => CompressedFormat::Bc7,
+        147 | 148 => CompressedFormat::Etc2Rgb,
+        149 | 150 => CompressedFormat::Etc2RgbA1,
+        151 | 152 => CompressedFormat::Etc2Rgba,
+        157 | 158 => CompressedFormat::Astc4x4,
+        159 | 160 => CompressedFormat::Astc5x4,
+        161 | 162 => CompressedFormat::Astc5x5,
+        163 | 164 => CompressedFormat::Astc6x5,
+        165 | 166 => CompressedFormat::Astc6x6,
+        167 | 168 => CompressedFormat::Astc8x5,
+        169 | 170 => CompressedFormat::Astc8x6,
+        171 | 172 => CompressedFormat::Astc8x8,
+        173 | 174 => CompressedFormat::Astc10x5,
+        175 | 176 => CompressedFormat::Astc10x6,
+        177 | 178 => CompressedFormat::Astc10x8,
+        179 | 180 => CompressedFormat::Astc10x10,
+        181 | 182 => CompressedFormat::Astc12x10,
+        183 | 184 => CompressedFormat::Astc12x12,
+        _ => return None,
+    };
+    let srgb = match vk_format {
+        132 | 134 | 136 | 138 | 146 | 148 | 150 | 152 => true,
+        157..=184 => vk_format & 1 == 0,
+        _ => false,
+    };
+    Some((format, srgb))
+}
+
+/// Decodes a KTX2 container holding a single 2D image with block compressed data. A level count
+/// of 0, which asks the loader to generate mip maps, is read as a single level.
+pub(crate) fn read_ktx2(bytes: &[u8]) -> Result<CompressedImage, ImageError> {
+    if !is_ktx2(bytes) {
+        return Err(ImageError::InvalidData);
+    }
+    let (format, srgb) = format(u32_at(bytes, 12)?).ok_or(ImageError::Unsupported)?;
+    let width = u32_at(bytes, 20)?;
+    let height = u32_at(bytes, 24)?;
+    let depth = u32_at(bytes, 28)?;
+    let layers = u32_at(bytes, 32)?;
+    let faces = u32_at(bytes, 36)?;
+    let level_count = u32_at(bytes, 40)?.max(1) as usize;
+    let supercompression = u32_at(bytes, 44)?;
+    if depth > 1 || layers > 1 || faces != 1 || supercompression != 0 {
+        return Err(ImageError::Unsupported);
+    }
+    if level_count > 32 {
+        return Err(ImageError::InvalidData);
+    }
+
+    let mut levels = Vec::with_capacity(level_count);
+    for level in 0..level_count {
+        let index = HEADER_SIZE + level * LEVEL_INDEX_SIZE;
+        let offset = u64_at(bytes, index)?;
+        let length = u64_at(bytes, index + 8)?;
+        let end = offset.checked_add(length).ok_or(ImageError::InvalidData)?;
+        levels.push(bytes.get(offset..end).ok_or(ImageError::InvalidData)?);
+    }
+    CompressedImage::from_levels(format, srgb, width, height, levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ktx2(vk_format: u32, width: u32, height: u32, levels: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = IDENTIFIER.to_vec();
+        for value in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32, 0, 0, 0, 0, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0; 16]);
+        // Levels are stored smallest first, after the level index.
+        let mut offset = HEADER_SIZE + levels.len() * LEVEL_INDEX_SIZE;
+        let mut offsets = alloc::vec![0; levels.len()];
+        for (level, data) in levels.iter().enumerate().rev() {
+            offsets[level] = offset;
+            offset += data.len();
+        }
+        for (level, data) in levels.iter().enumerate() {
+            bytes.extend_from_slice(&(offsets[level] as u64).to_le_bytes());
+            bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        }
+        for data in levels.iter().rev() {
+            bytes.extend_from_slice(data);
+        }
+        bytes
+    }
+
+    #[test]
+    fn mip_chain() {
+        let (base, mip) = ([1u8; 16], [2u8; 8]);
+        let image = CompressedImage::from_bytes(&ktx2(134, 8, 4, &[&base, &mip])).unwrap();
+        assert_eq!(image.format(), CompressedFormat::Bc1);
+        assert!(image.is_srgb());
+        assert_eq!((image.width(), image.height()), (8, 4));
+        assert_eq!(image.levels(), [base.to_vec(), mip.to_vec()]);
+        assert_eq!(image.level_dimensions(1), (4, 2));
+    }
+
+    #[test]
+    fn rejected() {
+        let truncated = ktx2(145, 8, 8, &[&[0; 16]]);
+        assert_eq!(read_ktx2(&truncated).err(), Some(ImageError::InvalidData));
+        let uncompressed = ktx2(37, 1, 1, &[&[0; 4]]);
+        assert_eq!(read_ktx2(&uncompressed).err(), Some(ImageError::Unsupported));
+        assert_eq!(format(165), Some((CompressedFormat::Astc6x6, false)));
+        assert_eq!(format(184), Some((CompressedFormat::Astc12x12, true)));
+        assert_eq!(format(145), Some((CompressedFormat::Bc7, false)));
+    }
+}
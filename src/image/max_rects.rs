@@ -0,0 +1,229 @@
+use crate::image::Rect;
+use alloc::vec::Vec;
+
+/// How the max rects packer scores the free rectangles a rectangle could be placed in. The free
+/// rectangle with the lowest score is picked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PackHeuristic {
+    /// Minimizes the shorter of the leftover sides. Works well for most inputs.
+    BestShortSideFit,
+    /// Minimizes the longer of the leftover sides.
+    BestLongSideFit,
+    /// Minimizes the leftover area.
+    BestAreaFit,
+    /// Places rectangles as close to the top left as possible, similar to the skyline packer.
+    BottomLeft,
+}
+
+/// Rectangle packer using the max rects algorithm. It tracks every maximal free rectangle, so it
+/// packs tighter than the skyline `Packer`, and packed rectangles can be released individually to
+/// reuse their space.
+pub struct MaxRectsPacker {
+    border: Rect,
+    free: Vec<Rect>,
+    used: Vec<Rect>,
+    heuristic: PackHeuristic,
+    allow_rotation: bool,
+}
+
+impl MaxRectsPacker {
+    /// Creates a new packer with the given width and height, using the best short side fit
+    /// heuristic and no rotation.
+    pub fn new(w: u32, h: u32) -> MaxRectsPacker {
+        MaxRectsPacker::with_options(w, h, PackHeuristic::BestShortSideFit, false)
+    }
+
+    /// Creates a new packer with the given width and height.
+    /// # Arguments
+    ///
+    /// * `heuristic` - How to choose where each rectangle is placed.
+    /// * `allow_rotation` - If rectangles may be rotated 90 degrees when that fits better. Rotated
+    ///   rectangles are returned with their width and height swapped.
+    pub fn with_options(w: u32, h: u32, heuristic: PackHeuristic, allow_rotation: bool) -> MaxRectsPacker {
+        let border = Rect::new(0, 0, w, h);
+        MaxRectsPacker {
+            border,
+            free: alloc::vec![border],
+            used: Vec::new(),
+            heuristic,
+            allow_rotation,
+        }
+    }
+
+    /// Clears all packed rectangles and starts with a fresh canvas.
+    pub fn clear(&mut self) {
+        self.free.clear();
+        self.free.push(self.border);
+        self.used.clear();
+    }
+
+    /// Packs a rectangle with a given width and height, returning the position of the rectangle in
+    /// the canvas if it could be packed. If rotation is allowed, the returned rectangle's width and
+    /// height may be swapped, meaning the content should be rotated 90 degrees to fit.
+    pub fn pack(&mut self, width: u32, height: u32) -> Option<Rect> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let mut best: Option<((u64, u64), Rect)> = None;
+        for free in &self.free {
+            let mut candidates = [Some((width, height)), None];
+            if self.allow_rotation && width != height {
+                candidates[1] = Some((height, width));
+            }
+            for (w, h) in candidates.iter().flatten().copied() {
+                if w > free.w || h > free.h {
+                    continue;
+                }
+                let score = self.score(free, w, h);
+                if best.map_or(true, |(best, _)| score < best) {
+                    best = Some((score, Rect::new(free.x, free.y, w, h)));
+                }
+            }
+        }
+        let (_, rect) = best?;
+        self.place(&rect);
+        self.used.push(rect);
+        Some(rect)
+    }
+
+    /// Releases a previously packed rectangle so its space can be reused. The rectangle must be
+    /// one returned by `pack` that hasn't been released yet.
+    ///
+    /// The maximal free rectangles are rebuilt from the remaining packed rectangles, so released
+    /// space joins every free area around it.
+    pub fn release(&mut self, rect: Rect) {
+        let index = self.used.iter().position(|used| *used == rect);
+        debug_assert!(index.is_some(), "Released rect isn't packed.");
+        if let Some(index) = index {
+            self.used.swap_remove(index);
+        }
+        self.free.clear();
+        self.free.push(self.border);
+        for used in core::mem::take(&mut self.used) {
+            self.place(&used);
+            self.used.push(used);
+        }
+    }
+
+    fn score(&self, free: &Rect, w: u32, h: u32) -> (u64, u64) {
+        let leftover_w = (free.w - w) as u64;
+        let leftover_h = (free.h - h) as u64;
+        let short = leftover_w.min(leftover_h);
+        let long = leftover_w.max(leftover_h);
+        match self.heuristic {
+            PackHeuristic::BestShortSideFit => (short, long),
+            PackHeuristic::BestLongSideFit => (long, short),
+            PackHeuristic::BestAreaFit => (free.w as u64 * free.h as u64 - w as u64 * h as u64, short),
+            PackHeuristic::BottomLeft => ((free.y + h) as u64, free.x as u64),
+        }
+    }
+
+    /// Splits every free rectangle overlapping the placed rectangle into the maximal rectangles
+    /// around it.
+    fn place(&mut self, rect: &Rect) {
+        let mut i = 0;
+        while i < self.free.len() {
+            let free = self.free[i];
+            if !free.intersects(rect) {
+                i += 1;
+                continue;
+            }
+            self.free.swap_remove(i);
+            if rect.x > free.x {
+                self.free.push(Rect::new(free.x, free.y, rect.x - free.x, free.h));
+            }
+            if rect.x + rect.w < free.x + free.w {
+                let x = rect.x + rect.w;
+                self.free.push(Rect::new(x, free.y, free.x + free.w - x, free.h));
+            }
+            if rect.y > free.y {
+                self.free.push(Rect::new(free.x, free.y, free.w, rect.y - free.y));
+            }
+            if rect.y + rect.h < free.y + free.h {
+                let y = rect.y + rect.h;
+                self.free.push(Rect::new(free.x, y, free.w, free.y + free.h - y));
+            }
+        }
+        self.prune();
+    }
+
+    /// Removes free rectangles that are contained in another free rectangle.
+    fn prune(&mut self) {
+        let mut i = 0;
+        while i < self.free.len() {
+            let contained = (0..self.free.len()).any(|j| {
+                j != i
+                    && self.free[j].contains(&self.free[i])
+                    // Of two identical rectangles, only the later one is removed.
+                    && (!self.free[i].contains(&self.free[j]) || j < i)
+            });
+            if contained {
+                self.free.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(rects: &[Rect]) -> bool {
+        rects.iter().enumerate().any(|(i, a)| rects[i + 1..].iter().any(|b| a.intersects(b)))
+    }
+
+    #[test]
+    fn pack_and_release() {
+        let mut packer = MaxRectsPacker::new(8, 8);
+        let rects: Vec<Rect> = (0..4).map(|_| packer.pack(4, 4).unwrap()).collect();
+        assert!(!overlaps(&rects));
+        assert!(packer.pack(1, 1).is_none());
+
+        // Releasing the right column makes room for a rectangle spanning both.
+        rects.iter().filter(|rect| rect.x == 4).for_each(|rect| packer.release(*rect));
+        assert_eq!(packer.pack(4, 8).map(|rect| (rect.x, rect.y)), Some((4, 0)));
+    }
+
+    #[test]
+    fn release_next_to_partial_overlap() {
+        // Two 4x4 blocks on top, and a 2x4 block at the bottom left, leaving a 6x4 free rect.
+        let mut packer = MaxRectsPacker::with_options(8, 8, PackHeuristic::BottomLeft, false);
+        let top_left = packer.pack(4, 4).unwrap();
+        packer.pack(4, 4).unwrap();
+        assert_eq!(packer.pack(2, 4).map(|rect| (rect.x, rect.y)), Some((0, 4)));
+
+        // The released block only partially borders the free rect below it, but the column they
+        // share is free from top to bottom.
+        packer.release(top_left);
+        assert_eq!(packer.pack(2, 8).map(|rect| (rect.x, rect.y)), Some((2, 0)));
+    }
+
+    #[test]
+    fn rotation() {
+        let mut packer = MaxRectsPacker::with_options(4, 8, PackHeuristic::BestAreaFit, true);
+        let rect = packer.pack(8, 4).unwrap();
+        assert_eq!((rect.w, rect.h), (4, 8));
+        assert!(MaxRectsPacker::new(4, 8).pack(8, 4).is_none());
+    }
+
+    #[test]
+    fn random_fill() {
+        let mut packer = MaxRectsPacker::with_options(64, 64, PackHeuristic::BottomLeft, true);
+        let mut rects = Vec::new();
+        let mut seed = 7u32;
+        for _ in 0..200 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let (w, h) = (1 + (seed >> 16) % 9, 1 + (seed >> 8) % 7);
+            if let Some(rect) = packer.pack(w, h) {
+                rects.push(rect);
+            }
+            if rects.len() > 10 && seed % 3 == 0 {
+                packer.release(rects.swap_remove((seed % 10) as usize));
+            }
+        }
+        assert!(!overlaps(&rects));
+        assert!(rects.iter().all(|rect| rect.x + rect.w <= 64 && rect.y + rect.h <= 64));
+    }
+}
@@ -0,0 +1,140 @@
+use super::{Image, ImageError};
+use crate::color::{linear_to_srgb, srgb_to_linear, ColorComponentType, ColorDescriptor, ColorLayoutFormat};
+use alloc::{vec, vec::Vec};
+
+/// Gets the range of source texels covered by a target texel when scaling down.
+fn span(index: u32, target: u32, source: u32) -> (usize, usize) {
+    let start = index as u64 * source as u64 / target as u64;
+    let end = ((index as u64 + 1) * source as u64).div_ceil(target as u64);
+    (start as usize, end as usize)
+}
+
+impl<T: ColorDescriptor> Image<T> {
+    /// Builds the mip chain below this image, each level half the size of the previous, stopping
+    /// after the requested number of levels or at 1x1. The full size image isn't included.
+    ///
+    /// Color channels are treated as sRGB encoded and averaged in linear light, and weighted by
+    /// alpha so fully transparent texels don't bleed their color into visible ones. The last
+    /// channel of `RGBA` and `BGRA` is alpha, and `RG` is gray and alpha. `R` has no color, so it's
+    /// treated as linear data, such as a mask or height, and averaged as is. Only images with 8 bit
+    /// components are supported.
+    pub fn mipmaps(&self, levels: u32) -> Result<Vec<Image<T>>, ImageError> {
+        if T::component_type() != ColorComponentType::U8 {
+            return Err(ImageError::Unsupported);
+        }
+        let bytes = self.as_bytes();
+        let components = T::layout().components();
+        let alpha = match T::layout() {
+            ColorLayoutFormat::RGBA | ColorLayoutFormat::BGRA => Some(3),
+            ColorLayoutFormat::RG => Some(1),
+            _ => None,
+        };
+        let linear = |channel: usize| Some(channel) == alpha || T::layout() == ColorLayoutFormat::R;
+        let decode: [f32; 256] = core::array::from_fn(|value| srgb_to_linear(value as u8));
+        let mut source: Vec<f32> = bytes
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| {
+                if linear(index % components) {
+                    value as f32 / 255.0
+                } else {
+                    decode[value as usize]
+                }
+            })
+            .collect();
+
+        let (mut width, mut height) = (self.width(), self.height());
+        let mut chain = Vec::new();
+        while chain.len() < levels as usize && (width > 1 || height > 1) {
+            let (target_width, target_height) = ((width / 2).max(1), (height / 2).max(1));
+            let mut target = vec![0.0; (target_width * target_height) as usize * components];
+            for y in 0..target_height {
+                let (top, bottom) = span(y, target_height, height);
+                for x in 0..target_width {
+                    let (left, right) = span(x, target_width, width);
+                    let mut weighted = [0.0f32; 4];
+                    let mut plain = [0.0f32; 4];
+                    let (mut weight, mut count) = (0.0, 0.0);
+                    for sy in top..bottom {
+                        for sx in left..right {
+                            let texel = &source[(sy * width as usize + sx) * components..][..components];
+                            let coverage = alpha.map_or(1.0, |alpha| texel[alpha]);
+                            for (c, value) in texel.iter().enumerate() {
+                                weighted[c] += value * coverage;
+                                plain[c] += value;
+                            }
+                            weight += coverage;
+                            count += 1.0;
+                        }
+                    }
+                    let pixel = &mut target[(y * target_width + x) as usize * components..][..components];
+                    for (c, value) in pixel.iter_mut().enumerate() {
+                        *value = if Some(c) == alpha || weight <= 0.0 {
+                            plain[c] / count
+                        } else {
+                            weighted[c] / weight
+                        };
+                    }
+                }
+            }
+
+            let bytes: Vec<u8> = target
+                .iter()
+                .enumerate()
+                .map(|(index, &value)| {
+                    if linear(index % components) {
+                        (value * 255.0 + 0.5) as u8
+                    } else {
+                        linear_to_srgb(value)
+                    }
+                })
+                .collect();
+            chain.push(
+                Image::from_raw_bytes(&bytes, target_width, target_height).ok_or(ImageError::Unsupported)?,
+            );
+            source = target;
+            width = target_width;
+            height = target_height;
+        }
+        Ok(chain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{R8, RG8, RGBA8};
+
+    #[test]
+    fn linear_alpha_weighted() {
+        let transparent_red = RGBA8::new(255, 0, 0, 0);
+        let image = Image::from_vec(vec![RGBA8::WHITE, RGBA8::BLACK, transparent_red, transparent_red], 2, 2);
+        let chain = image.mipmaps(4).unwrap();
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain[0].as_slice(), [RGBA8::new(188, 188, 188, 128)]);
+
+        let clear = Image::from_vec(vec![transparent_red, RGBA8::TRANSPARENT], 2, 1);
+        assert_eq!(clear.mipmaps(1).unwrap()[0].as_slice(), [RGBA8::new(188, 0, 0, 0)]);
+
+        // Gray and alpha: the transparent gray doesn't bleed in, and alpha is averaged linearly.
+        let image = Image::from_vec(
+            vec![RG8::new(255, 255), RG8::new(0, 255), RG8::new(100, 0), RG8::new(100, 0)],
+            2,
+            2,
+        );
+        assert_eq!(image.mipmaps(1).unwrap()[0].as_slice(), [RG8::new(188, 128)]);
+
+        // Single channels are linear data.
+        let image = Image::from_vec(vec![R8::new(255), R8::new(0)], 2, 1);
+        assert_eq!(image.mipmaps(1).unwrap()[0].as_slice(), [R8::new(128)]);
+    }
+
+    #[test]
+    fn odd_sizes() {
+        let image = Image::from_color(R8::new(200), 5, 3);
+        let chain = image.mipmaps(8).unwrap();
+        let sizes: Vec<_> = chain.iter().map(|level| (level.width(), level.height())).collect();
+        assert_eq!(sizes, [(2, 1), (1, 1)]);
+        assert_eq!(chain[1].as_slice(), [R8::new(200)]);
+    }
+}
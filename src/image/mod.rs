@@ -1,7 +1,34 @@
+mod animation;
+mod bc;
+mod bmp;
+mod compressed;
+mod convert;
+mod dds;
+mod draw;
+mod error;
+mod etc;
+mod format;
+mod gif;
 mod image;
+mod jpeg;
+mod ktx2;
+mod max_rects;
+mod mipmap;
 mod packer;
 mod png;
+mod qoi;
 mod resize;
+mod sdf;
+mod sprite_sheet;
+mod tga;
+mod transform;
 
+pub use animation::AnimationFrame;
+pub use compressed::{CompressedFormat, CompressedImage};
+pub use error::ImageError;
+pub use format::ImageFormat;
 pub use image::Image;
+pub use max_rects::{MaxRectsPacker, PackHeuristic};
 pub use packer::{Packer, Rect};
+pub use resize::ResizeFilter;
+pub use sprite_sheet::{SpriteSheet, SpriteSheetMetadata};
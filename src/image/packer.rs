@@ -1,7 +1,7 @@
 use alloc::vec::Vec;
 
 /// A basic rectangle type used in the packer.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Rect {
     pub x: u32,
     pub y: u32,
@@ -40,12 +40,20 @@ impl Rect {
     }
 
     #[inline(always)]
-    fn contains(&self, other: &Rect) -> bool {
+    pub(crate) fn contains(&self, other: &Rect) -> bool {
         self.left() <= other.left()
             && self.right() >= other.right()
             && self.top() <= other.top()
             && self.bottom() >= other.bottom()
     }
+
+    #[inline(always)]
+    pub(crate) fn intersects(&self, other: &Rect) -> bool {
+        self.left() <= other.right()
+            && self.right() >= other.left()
+            && self.top() <= other.bottom()
+            && self.bottom() >= other.top()
+    }
 }
 
 struct Skyline {
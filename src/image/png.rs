@@ -1,44 +1,242 @@
-use super::Image;
-use crate::color::RGBA8;
+use super::animation::AnimationFrame;
+use super::{Image, ImageError};
+use crate::color::{blend_over, luma, ColorComponentType, ColorDescriptor, ColorLayoutFormat, RGBA8};
 use alloc::{vec, vec::Vec};
-use png::{ColorType, Decoder};
+use core::time::Duration;
+use png::{BitDepth, BlendOp, ColorType, Decoder, DisposeOp, Encoder, Reader, Transformations};
 
 /// Interpret a slice of bytes as a PNG and decodes it into an RGBA image.
 pub fn read_png(bytes: &[u8]) -> Image<RGBA8> {
-    let decoder = Decoder::new(bytes);
-    let (info, mut reader) = decoder.read_info().expect("Unable to read PNG info.");
-    let mut input = vec![0; info.buffer_size()];
-    reader.next_frame(&mut input).expect("Unable to read PNG payload.");
-
-    match info.color_type {
-        ColorType::RGB => {
-            let mut output = Vec::with_capacity((input.len() / 3) * 4);
-            for rgb in input.chunks_exact(3) {
-                output.push(RGBA8::new(rgb[0], rgb[1], rgb[2], 255));
-            }
-            Image::from_vec(output, info.width, info.height)
+    read_png_as(bytes).expect("Unable to read PNG.")
+}
+
+/// Decodes a PNG into an image of the requested color type. Palettes and tRNS transparency are
+/// expanded, and 16 bit components are reduced to 8 bits. Grayscale stays grayscale if the target
+/// has fewer than three components, and color is converted to luma.
+pub fn read_png_as<T: ColorDescriptor>(bytes: &[u8]) -> Result<Image<T>, ImageError> {
+    if T::component_type() != ColorComponentType::U8 {
+        return Err(ImageError::Unsupported);
+    }
+    let mut reader = reader(bytes)?;
+    let mut input = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut input).map_err(|_| ImageError::InvalidData)?;
+    let output = convert(&input, info.color_type, info.width, info.height, T::layout())?;
+    Image::from_raw_bytes(&output, info.width, info.height).ok_or(ImageError::InvalidData)
+}
+
+/// Decodes every frame of an animated PNG, composited onto a canvas the size of the image. A PNG
+/// without animation decodes to a single frame with no delay. The default image is skipped if
+/// it isn't part of the animation.
+pub(crate) fn read_apng(bytes: &[u8]) -> Result<Vec<AnimationFrame>, ImageError> {
+    let mut reader = reader(bytes)?;
+    let (width, height) = reader.info().size();
+    let num_frames = match reader.info().animation_control {
+        Some(control) => control.num_frames,
+        None => {
+            return Ok(vec![AnimationFrame {
+                image: read_png_as(bytes)?,
+                delay: Duration::ZERO,
+            }])
         }
-        ColorType::RGBA => {
-            let mut output = Vec::with_capacity(input.len());
-            for rgba in input.chunks_exact(4) {
-                output.push(RGBA8::new(rgba[0], rgba[1], rgba[2], rgba[3]));
-            }
-            Image::from_vec(output, info.width, info.height)
+    };
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    if reader.info().frame_control.is_none() {
+        reader.next_frame(&mut buffer).map_err(|_| ImageError::InvalidData)?;
+    }
+
+    let mut canvas = Image::from_color(RGBA8::TRANSPARENT, width, height);
+    // The frame count comes from the file, so frames aren't preallocated for it.
+    let mut frames = Vec::new();
+    for _ in 0..num_frames {
+        let info = reader.next_frame(&mut buffer).map_err(|_| ImageError::InvalidData)?;
+        let control = reader.info().frame_control.ok_or(ImageError::InvalidData)?;
+        let (left, top) = (control.x_offset, control.y_offset);
+        let right = left.checked_add(info.width).ok_or(ImageError::InvalidData)?;
+        let bottom = top.checked_add(info.height).ok_or(ImageError::InvalidData)?;
+        if right > width || bottom > height {
+            return Err(ImageError::InvalidData);
         }
-        ColorType::Grayscale => {
-            let mut output = Vec::with_capacity(input.len() * 4);
-            for g in input {
-                output.push(RGBA8::new(g, g, g, 255));
-            }
-            Image::from_vec(output, info.width, info.height)
+        let pixels = convert(&buffer, info.color_type, info.width, info.height, ColorLayoutFormat::RGBA)?;
+
+        let previous = match control.dispose_op {
+            DisposeOp::Previous => Some(canvas.clone()),
+            _ => None,
+        };
+        for (index, rgba) in pixels.chunks_exact(4).enumerate() {
+            let (x, y) = (left + index as u32 % info.width, top + index as u32 / info.width);
+            let source = RGBA8::new(rgba[0], rgba[1], rgba[2], rgba[3]);
+            let color = match control.blend_op {
+                BlendOp::Source => source,
+                BlendOp::Over => blend_over(canvas.get(x, y), source),
+            };
+            canvas.set(x, y, color);
         }
-        ColorType::GrayscaleAlpha => {
-            let mut output = Vec::with_capacity(input.len() * 2);
-            for ga in input.chunks_exact(2) {
-                output.push(RGBA8::new(ga[0], ga[0], ga[0], ga[1]));
+        frames.push(AnimationFrame {
+            image: canvas.clone(),
+            delay: delay(control.delay_num, control.delay_den),
+        });
+
+        match (control.dispose_op, previous) {
+            (DisposeOp::Previous, Some(previous)) => canvas = previous,
+            (DisposeOp::Background, _) => {
+                for y in top..bottom {
+                    for x in left..right {
+                        canvas.set(x, y, RGBA8::TRANSPARENT);
+                    }
+                }
             }
-            Image::from_vec(output, info.width, info.height)
+            _ => {}
+        }
+    }
+    Ok(frames)
+}
+
+/// Frame delays are a fraction of a second. A denominator of 0 means hundredths.
+fn delay(numerator: u16, denominator: u16) -> Duration {
+    let denominator = if denominator == 0 {
+        100
+    } else {
+        denominator
+    };
+    Duration::from_micros(numerator as u64 * 1_000_000 / denominator as u64)
+}
+
+/// Creates a reader that expands palettes and tRNS transparency, and reduces 16 bit components to
+/// 8 bits.
+fn reader(bytes: &[u8]) -> Result<Reader<&[u8]>, ImageError> {
+    let mut decoder = Decoder::new(bytes);
+    decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
+    let reader = decoder.read_info().map_err(|_| ImageError::InvalidData)?;
+    let (width, height) = reader.info().size();
+    super::format::check_dimensions(width, height)?;
+    if reader.output_color_type().1 != BitDepth::Eight {
+        return Err(ImageError::Unsupported);
+    }
+    Ok(reader)
+}
+
+/// Converts decoded 8 bit pixels to the target layout.
+fn convert(
+    input: &[u8],
+    color_type: ColorType,
+    width: u32,
+    height: u32,
+    target: ColorLayoutFormat,
+) -> Result<Vec<u8>, ImageError> {
+    let source = match color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::Rgb => 3,
+        ColorType::Rgba => 4,
+        ColorType::Indexed => return Err(ImageError::Unsupported),
+    };
+    let count = (width * height) as usize;
+    let input = input.get(..count * source).ok_or(ImageError::InvalidData)?;
+    if source == target.components() && target != ColorLayoutFormat::BGRA {
+        return Ok(input.to_vec());
+    }
+
+    let mut output = Vec::with_capacity(count * target.components());
+    for pixel in input.chunks_exact(source) {
+        let (r, g, b, a) = match source {
+            1 => (pixel[0], pixel[0], pixel[0], 255),
+            2 => (pixel[0], pixel[0], pixel[0], pixel[1]),
+            3 => (pixel[0], pixel[1], pixel[2], 255),
+            _ => (pixel[0], pixel[1], pixel[2], pixel[3]),
+        };
+        match target {
+            ColorLayoutFormat::R => output.push(luma(r, g, b)),
+            ColorLayoutFormat::RG => output.extend_from_slice(&[luma(r, g, b), a]),
+            ColorLayoutFormat::RGB => output.extend_from_slice(&[r, g, b]),
+            ColorLayoutFormat::RGBA => output.extend_from_slice(&[r, g, b, a]),
+            ColorLayoutFormat::BGRA => output.extend_from_slice(&[b, g, r, a]),
         }
-        ColorType::Indexed => panic!("PNG Indexed color type is unsupported."),
+    }
+    Ok(output)
+}
+
+/// Encodes an image as an 8 bit PNG.
+pub fn write_png<T: ColorDescriptor>(image: &Image<T>) -> Result<Vec<u8>, ImageError> {
+    let (layout, bytes) = super::format::encodable_bytes(image)?;
+    let color_type = match layout {
+        ColorLayoutFormat::R => ColorType::Grayscale,
+        ColorLayoutFormat::RG => ColorType::GrayscaleAlpha,
+        ColorLayoutFormat::RGB => ColorType::Rgb,
+        _ => ColorType::Rgba,
+    };
+    let mut output = Vec::new();
+    let mut encoder = Encoder::new(&mut output, image.width(), image.height());
+    encoder.set_color(color_type);
+    encoder.set_depth(BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|_| ImageError::InvalidData)?;
+    writer.write_image_data(&bytes).map_err(|_| ImageError::InvalidData)?;
+    writer.finish().map_err(|_| ImageError::InvalidData)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{R8, RG8};
+
+    fn encode(
+        width: u32,
+        height: u32,
+        setup: impl FnOnce(&mut Encoder<&mut Vec<u8>>),
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut encoder = Encoder::new(&mut output, width, height);
+        setup(&mut encoder);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+        output
+    }
+
+    #[test]
+    fn palette_with_transparency() {
+        let png = encode(
+            3,
+            1,
+            |encoder| {
+                encoder.set_color(ColorType::Indexed);
+                encoder.set_depth(BitDepth::Eight);
+                encoder.set_palette(vec![255, 0, 0, 0, 255, 0, 0, 0, 255]);
+                // Entries past the end of tRNS are opaque.
+                encoder.set_trns(vec![0, 128]);
+            },
+            &[0, 1, 2],
+        );
+        let image = read_png_as::<RGBA8>(&png).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::new(255, 0, 0, 0), RGBA8::new(0, 255, 0, 128), RGBA8::BLUE]);
+        let image = read_png_as::<RG8>(&png).unwrap();
+        assert_eq!(image.as_slice()[1].g, 128);
+    }
+
+    #[test]
+    fn sixteen_bit() {
+        let rgba = [0x12, 0x34, 0xff, 0xff, 0x00, 0x00, 0x80, 0x00];
+        let png = encode(
+            1,
+            1,
+            |encoder| {
+                encoder.set_color(ColorType::Rgba);
+                encoder.set_depth(BitDepth::Sixteen);
+            },
+            &rgba,
+        );
+        assert_eq!(read_png_as::<RGBA8>(&png).unwrap().as_slice(), [RGBA8::new(0x12, 0xff, 0x00, 0x80)]);
+
+        let gray = encode(
+            2,
+            1,
+            |encoder| {
+                encoder.set_color(ColorType::Grayscale);
+                encoder.set_depth(BitDepth::Sixteen);
+            },
+            &[0xab, 0xcd, 0x01, 0x02],
+        );
+        assert_eq!(read_png_as::<R8>(&gray).unwrap().as_slice(), [R8::new(0xab), R8::new(0x01)]);
     }
 }
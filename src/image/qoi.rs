@@ -0,0 +1,35 @@
+use super::format::checked_image;
+use super::{Image, ImageError};
+use crate::color::{ColorDescriptor, ColorLayoutFormat, RGBA8};
+use alloc::vec::Vec;
+use qoi::{Channels, Decoder};
+
+/// Decodes a QOI image.
+pub fn read_qoi(bytes: &[u8]) -> Result<Image<RGBA8>, ImageError> {
+    let decoder = Decoder::new(bytes).map_err(|_| ImageError::InvalidData)?;
+    let (width, height) = (decoder.header().width, decoder.header().height);
+    super::format::check_dimensions(width, height)?;
+    let input = decoder.with_channels(Channels::Rgba).decode_to_vec().map_err(|_| ImageError::InvalidData)?;
+    let output: Vec<RGBA8> =
+        input.chunks_exact(4).map(|rgba| RGBA8::new(rgba[0], rgba[1], rgba[2], rgba[3])).collect();
+    checked_image(output, width, height)
+}
+
+/// Encodes an image as a QOI. Single channel images are stored as gray RGB, and two channel images
+/// as gray RGBA.
+pub fn write_qoi<T: ColorDescriptor>(image: &Image<T>) -> Result<Vec<u8>, ImageError> {
+    let (layout, bytes) = super::format::encodable_bytes(image)?;
+    let expanded: Vec<u8>;
+    let data = match layout {
+        ColorLayoutFormat::R => {
+            expanded = bytes.iter().flat_map(|&r| [r, r, r]).collect();
+            &expanded
+        }
+        ColorLayoutFormat::RG => {
+            expanded = bytes.chunks_exact(2).flat_map(|rg| [rg[0], rg[0], rg[0], rg[1]]).collect();
+            &expanded
+        }
+        _ => &bytes[..],
+    };
+    qoi::encode_to_vec(data, image.width(), image.height()).map_err(|_| ImageError::InvalidData)
+}
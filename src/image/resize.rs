@@ -22,15 +22,104 @@
 //
 // Below is from https://github.com/image-rs/image/blob/master/src/imageops/sample.rs
 
+use crate::color::{f16_to_f32, f32_to_f16, ColorComponentType, ColorDescriptor, ColorLayoutFormat};
 use crate::image::Image;
-use crate::{color::RGBA8, math::Float};
+use crate::math::Float;
 use alloc::vec::Vec;
 
-impl Image<RGBA8> {
+/// The filter used to resample an image when resizing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResizeFilter {
+    /// Picks the closest source pixel. Keeps hard edges, which suits pixel art.
+    Nearest,
+    /// Interpolates linearly between the closest source pixels.
+    Bilinear,
+    /// Catmull-Rom cubic interpolation. Sharper than bilinear.
+    Bicubic,
+    /// Averages the source pixels covered by each output pixel. Suited to downscaling by whole
+    /// factors.
+    Box,
+    /// Lanczos with a window of 3. The sharpest filter, at the cost of slight ringing.
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    /// How far from the center the filter reaches, in source pixels at a scale of 1.
+    fn support(&self) -> f32 {
+        match self {
+            ResizeFilter::Nearest | ResizeFilter::Box => 0.5,
+            ResizeFilter::Bilinear => 1.0,
+            ResizeFilter::Bicubic => 2.0,
+            ResizeFilter::Lanczos3 => 3.0,
+        }
+    }
+
+    fn kernel(&self, x: f32) -> f32 {
+        match self {
+            ResizeFilter::Nearest | ResizeFilter::Box => {
+                if (-0.5..0.5).contains(&x) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Bilinear => (1.0 - x.abs()).max(0.0),
+            ResizeFilter::Bicubic => catmull_rom(x),
+            ResizeFilter::Lanczos3 => lanczos3(x),
+        }
+    }
+}
+
+impl<T: ColorDescriptor> Image<T> {
     /// Performs a lanczos resampling of the image.
-    pub fn resize(&self, width: u32, height: u32) -> Image<RGBA8> {
-        let image = vertical_sample(self, height);
-        horizontal_sample(&image, width)
+    pub fn resize(&self, width: u32, height: u32) -> Image<T> {
+        self.resize_with(width, height, ResizeFilter::Lanczos3)
+    }
+
+    /// Resamples the image to the given size with the given filter. Components are filtered
+    /// independently, so colors with straight alpha can darken along transparent edges. Use
+    /// `resize_premultiplied` for those.
+    pub fn resize_with(&self, width: u32, height: u32, filter: ResizeFilter) -> Image<T> {
+        self.resample(width, height, filter, false)
+    }
+
+    /// Resamples the image to the given size with the given filter, weighting color by alpha. This
+    /// keeps the color of fully transparent pixels from bleeding into visible ones, which avoids
+    /// dark halos around sprites. Only `RGBA` and `BGRA` layouts have alpha, other layouts are
+    /// resampled as in `resize_with`.
+    pub fn resize_premultiplied(&self, width: u32, height: u32, filter: ResizeFilter) -> Image<T> {
+        self.resample(width, height, filter, true)
+    }
+
+    fn resample(&self, width: u32, height: u32, filter: ResizeFilter, premultiply: bool) -> Image<T> {
+        assert!(width > 0 && height > 0, "Neither width or height can be 0.");
+        if filter == ResizeFilter::Nearest {
+            return nearest(self, width, height);
+        }
+        let channels = T::layout().components();
+        let alpha = match T::layout() {
+            ColorLayoutFormat::RGBA | ColorLayoutFormat::BGRA if premultiply => Some(3),
+            _ => None,
+        };
+
+        let mut data = to_f32(self);
+        if let Some(alpha) = alpha {
+            for pixel in data.chunks_exact_mut(channels) {
+                let a = pixel[alpha];
+                pixel.iter_mut().take(alpha).for_each(|c| *c *= a);
+            }
+        }
+        let data = vertical_sample(&data, self.width(), self.height(), channels, height, filter);
+        let mut data = horizontal_sample(&data, self.width(), height, channels, width, filter);
+        if let Some(alpha) = alpha {
+            for pixel in data.chunks_exact_mut(channels) {
+                let a = pixel[alpha];
+                if a > 0.0 {
+                    pixel.iter_mut().take(alpha).for_each(|c| *c /= a);
+                }
+            }
+        }
+        from_f32(&data, width, height)
     }
 }
 
@@ -64,127 +153,202 @@ fn lanczos3(x: f32) -> f32 {
     }
 }
 
-fn vertical_sample(image: &Image<RGBA8>, new_height: u32) -> Image<RGBA8> {
-    const SUPPORT: f32 = 3.0;
-    let (width, height) = (image.width(), image.height());
-    let mut out = Image::from_color(RGBA8::MAGENTA, width, new_height);
-    let mut ws = Vec::new();
+fn catmull_rom(x: f32) -> f32 {
+    let x = x.abs();
+    if x < 1.0 {
+        1.5 * x * x * x - 2.5 * x * x + 1.0
+    } else if x < 2.0 {
+        -0.5 * x * x * x + 2.5 * x * x - 4.0 * x + 2.0
+    } else {
+        0.0
+    }
+}
 
-    let ratio = height as f32 / new_height as f32;
+fn nearest<T: ColorDescriptor>(image: &Image<T>, width: u32, height: u32) -> Image<T> {
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        let source_y =
+            ((y as u64 * image.height() as u64 + image.height() as u64 / 2) / height as u64) as u32;
+        let source_y = source_y.min(image.height() - 1);
+        for x in 0..width {
+            let source_x =
+                ((x as u64 * image.width() as u64 + image.width() as u64 / 2) / width as u64) as u32;
+            pixels.push(image.get(source_x.min(image.width() - 1), source_y));
+        }
+    }
+    Image::from_vec(pixels, width, height)
+}
+
+/// Reads the components of an image into floats, with 8 bit components normalized to [0, 1]. 16
+/// bit integer components keep their raw values.
+fn to_f32<T: ColorDescriptor>(image: &Image<T>) -> Vec<f32> {
+    let bytes = image.as_bytes();
+    match T::component_type() {
+        ColorComponentType::U8 => bytes.iter().map(|&c| c as f32 / 255.0).collect(),
+        ColorComponentType::U16 => {
+            bytes.chunks_exact(2).map(|c| u16::from_ne_bytes([c[0], c[1]]) as f32).collect()
+        }
+        ColorComponentType::F16 => {
+            bytes.chunks_exact(2).map(|c| f16_to_f32(u16::from_ne_bytes([c[0], c[1]]))).collect()
+        }
+        ColorComponentType::F32 => {
+            bytes.chunks_exact(4).map(|c| f32::from_ne_bytes([c[0], c[1], c[2], c[3]])).collect()
+        }
+    }
+}
+
+fn from_f32<T: ColorDescriptor>(data: &[f32], width: u32, height: u32) -> Image<T> {
+    let bytes: Vec<u8> = match T::component_type() {
+        ColorComponentType::U8 => data.iter().map(|&c| (clamp(c, 0.0, 1.0) * 255.0).round() as u8).collect(),
+        ColorComponentType::U16 => {
+            data.iter().flat_map(|&c| (clamp(c, 0.0, u16::MAX as f32).round() as u16).to_ne_bytes()).collect()
+        }
+        ColorComponentType::F16 => data.iter().flat_map(|&c| f32_to_f16(c).to_ne_bytes()).collect(),
+        ColorComponentType::F32 => data.iter().flat_map(|c| c.to_ne_bytes()).collect(),
+    };
+    // The data always holds every component of a width by height image.
+    Image::from_raw_bytes(&bytes, width, height).expect("Resized data doesn't match the image size.")
+}
+
+/// Computes the first source index and the normalized weights contributing to an output index.
+fn weights(output: u32, ratio: f32, length: u32, filter: ResizeFilter, ws: &mut Vec<f32>) -> u32 {
     let sratio = if ratio < 1.0 {
         1.0
     } else {
         ratio
     };
-    let src_support = SUPPORT * sratio;
+    let src_support = filter.support() * sratio;
 
-    for outy in 0..new_height {
-        let inputy = (outy as f32 + 0.5) * ratio;
+    // Find the point in the input corresponding to the centre of the output pixel.
+    let input = (output as f32 + 0.5) * ratio;
 
-        let left = (inputy - src_support).floor() as i64;
-        let left = clamp(left, 0, <i64 as From<_>>::from(height) - 1) as u32;
+    // Left and right are slice bounds for the input pixels relevant to the output pixel. Pixel i
+    // is relevant if and only if (i >= left) && (i < right). Invariant: 0 <= left < right <= length
+    let left = (input - src_support).floor() as i64;
+    let left = clamp(left, 0, <i64 as From<_>>::from(length) - 1) as u32;
 
-        let right = (inputy + src_support).ceil() as i64;
-        let right = clamp(right, <i64 as From<_>>::from(left) + 1, <i64 as From<_>>::from(height)) as u32;
+    let right = (input + src_support).ceil() as i64;
+    let right = clamp(right, <i64 as From<_>>::from(left) + 1, <i64 as From<_>>::from(length)) as u32;
 
-        let inputy = inputy - 0.5;
+    // Go back to left boundary of pixel, to properly compare with i below, as the kernel treats
+    // the centre of a pixel as 0.
+    let input = input - 0.5;
 
-        ws.clear();
-        let mut sum = 0.0;
-        for i in left..right {
-            let w = lanczos3((i as f32 - inputy) / sratio);
-            ws.push(w);
-            sum += w;
-        }
+    ws.clear();
+    let mut sum = 0.0;
+    for i in left..right {
+        let w = filter.kernel((i as f32 - input) / sratio);
+        ws.push(w);
+        sum += w;
+    }
+    if sum == 0.0 {
+        // Narrow filters can miss every pixel when upscaling, so fall back to the closest one.
+        ws.iter_mut().for_each(|w| *w = 0.0);
+        let closest = clamp(input.round() as i64, left as i64, right as i64 - 1) - left as i64;
+        ws[closest as usize] = 1.0;
+    } else {
         ws.iter_mut().for_each(|w| *w /= sum);
+    }
+    left
+}
 
-        for x in 0..width {
-            let mut t = (0.0, 0.0, 0.0, 0.0);
+fn vertical_sample(
+    data: &[f32],
+    width: u32,
+    height: u32,
+    channels: usize,
+    new_height: u32,
+    filter: ResizeFilter,
+) -> Vec<f32> {
+    let mut out = Vec::with_capacity(width as usize * new_height as usize * channels);
+    let mut ws = Vec::new();
+    let ratio = height as f32 / new_height as f32;
 
+    for outy in 0..new_height {
+        let left = weights(outy, ratio, height, filter, &mut ws);
+        for x in 0..width as usize {
+            let mut t = [0.0f32; 4];
             for (i, w) in ws.iter().enumerate() {
-                let p = image.get(x, left + i as u32);
-                let vec: (f32, f32, f32, f32) = p.into();
-                t.0 += vec.0 * w;
-                t.1 += vec.1 * w;
-                t.2 += vec.2 * w;
-                t.3 += vec.3 * w;
+                let index = ((left as usize + i) * width as usize + x) * channels;
+                for c in 0..channels {
+                    t[c] += data[index + c] * w;
+                }
             }
-
-            let t = RGBA8::from_f32(t.0, t.1, t.2, t.3);
-
-            out.set(x, outy, t);
+            out.extend_from_slice(&t[..channels]);
         }
     }
 
     out
 }
 
-fn horizontal_sample(image: &Image<RGBA8>, new_width: u32) -> Image<RGBA8> {
-    const SUPPORT: f32 = 3.0;
-    let (width, height) = (image.width(), image.height());
-    let mut out = Image::from_color(RGBA8::MAGENTA, new_width, height);
+fn horizontal_sample(
+    data: &[f32],
+    width: u32,
+    height: u32,
+    channels: usize,
+    new_width: u32,
+    filter: ResizeFilter,
+) -> Vec<f32> {
+    let mut out = alloc::vec![0.0; new_width as usize * height as usize * channels];
     let mut ws = Vec::new();
-
     let ratio = width as f32 / new_width as f32;
-    let sratio = if ratio < 1.0 {
-        1.0
-    } else {
-        ratio
-    };
-    let src_support = SUPPORT * sratio;
-
-    for outx in 0..new_width {
-        // Find the point in the input image corresponding to the centre
-        // of the current pixel in the output image.
-        let inputx = (outx as f32 + 0.5) * ratio;
-
-        // Left and right are slice bounds for the input pixels relevant
-        // to the output pixel we are calculating.  Pixel x is relevant
-        // if and only if (x >= left) && (x < right).
-
-        // Invariant: 0 <= left < right <= width
-
-        let left = (inputx - src_support).floor() as i64;
-        let left = clamp(left, 0, <i64 as From<_>>::from(width) - 1) as u32;
-
-        let right = (inputx + src_support).ceil() as i64;
-        let right = clamp(right, <i64 as From<_>>::from(left) + 1, <i64 as From<_>>::from(width)) as u32;
-
-        // Go back to left boundary of pixel, to properly compare with i
-        // below, as the kernel treats the centre of a pixel as 0.
-        let inputx = inputx - 0.5;
-
-        ws.clear();
-        let mut sum = 0.0;
-        for i in left..right {
-            let w = lanczos3((i as f32 - inputx) / sratio);
-            ws.push(w);
-            sum += w;
-        }
-        ws.iter_mut().for_each(|w| *w /= sum);
-
-        for y in 0..height {
-            let mut t = (0.0, 0.0, 0.0, 0.0);
 
+    for outx in 0..new_width as usize {
+        let left = weights(outx as u32, ratio, width, filter, &mut ws);
+        for y in 0..height as usize {
+            let mut t = [0.0f32; 4];
             for (i, w) in ws.iter().enumerate() {
-                let p = image.get(left + i as u32, y);
-                let vec: (f32, f32, f32, f32) = p.into();
-                t.0 += vec.0 * w;
-                t.1 += vec.1 * w;
-                t.2 += vec.2 * w;
-                t.3 += vec.3 * w;
+                let index = (y * width as usize + left as usize + i) * channels;
+                for c in 0..channels {
+                    t[c] += data[index + c] * w;
+                }
             }
-
-            let t = RGBA8::from_f32(
-                clamp(t.0, 0.0, 1.0),
-                clamp(t.1, 0.0, 1.0),
-                clamp(t.2, 0.0, 1.0),
-                clamp(t.3, 0.0, 1.0),
-            );
-
-            out.set(outx, y, t);
+            let index = (y * new_width as usize + outx) * channels;
+            out[index..index + channels].copy_from_slice(&t[..channels]);
         }
     }
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{R16UI, R8, RGBA16F, RGBA8};
+
+    #[test]
+    fn nearest_and_box() {
+        let image = Image::from_vec(alloc::vec![R8::new(0), R8::new(100), R8::new(200), R8::new(50)], 2, 2);
+        let up = image.resize_with(4, 4, ResizeFilter::Nearest);
+        assert_eq!(up.get(1, 1), R8::new(0));
+        assert_eq!(up.get(2, 1), R8::new(100));
+        assert_eq!(up.get(3, 3), R8::new(50));
+
+        let down = image.resize_with(1, 1, ResizeFilter::Box);
+        assert_eq!(down.as_slice(), [R8::new(88)]);
+    }
+
+    #[test]
+    fn premultiplied_edges() {
+        // Transparent black next to opaque white shouldn't darken the white.
+        let image = Image::from_vec(alloc::vec![RGBA8::new(0, 0, 0, 0), RGBA8::WHITE], 2, 1);
+        let straight = image.resize_with(1, 1, ResizeFilter::Bilinear);
+        assert_eq!(straight.as_slice(), [RGBA8::new(128, 128, 128, 128)]);
+        let premultiplied = image.resize_premultiplied(1, 1, ResizeFilter::Bilinear);
+        assert_eq!(premultiplied.as_slice(), [RGBA8::new(255, 255, 255, 128)]);
+    }
+
+    #[test]
+    fn wide_components() {
+        let image = Image::from_vec(
+            alloc::vec![RGBA16F::new(0.0, 2.0, 8.0, 1.0), RGBA16F::new(1.0, 4.0, 0.5, 1.0)],
+            2,
+            1,
+        );
+        let down = image.resize_with(1, 1, ResizeFilter::Box);
+        assert_eq!(down.as_slice(), [RGBA16F::new(0.5, 3.0, 4.25, 1.0)]);
+
+        let image = Image::from_vec(alloc::vec![R16UI::new(1000), R16UI::new(60001)], 2, 1);
+        assert_eq!(image.resize_with(1, 1, ResizeFilter::Box).as_slice(), [R16UI::new(30501)]);
+    }
+}
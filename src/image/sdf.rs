@@ -0,0 +1,159 @@
+use crate::color::{Channel, ColorConvert, R8};
+use crate::image::Image;
+use alloc::{vec, vec::Vec};
+
+/// Stands in for infinity in the distance transform, as infinity would produce NaNs.
+const FAR: f32 = 1e20;
+
+impl<T: ColorConvert> Image<T> {
+    /// Generates a signed distance field from the shape in one channel of the image, such as its
+    /// alpha. Pixels where the channel is at least half are inside the shape. The distance is exact
+    /// Euclidean distance, computed with the Felzenszwalb and Huttenlocher distance transform.
+    ///
+    /// The edge of the shape maps to 128, and values rise to 255 `spread` pixels inside the shape
+    /// and fall to 0 `spread` pixels outside of it. Larger spreads suit wider outlines and glows,
+    /// at the cost of precision near the edge.
+    pub fn signed_distance_field(&self, channel: Channel, spread: f32) -> Image<R8> {
+        assert!(spread > 0.0, "spread must be greater than 0.");
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let inside: Vec<bool> =
+            self.as_slice().iter().map(|&pixel| channel.read(pixel.to_rgba8()) >= 128).collect();
+
+        // Squared distance to the nearest inside pixel, and to the nearest outside pixel.
+        let mut to_inside: Vec<f32> = inside
+            .iter()
+            .map(|&inside| {
+                if inside {
+                    0.0
+                } else {
+                    FAR
+                }
+            })
+            .collect();
+        let mut to_outside: Vec<f32> = inside
+            .iter()
+            .map(|&inside| {
+                if inside {
+                    FAR
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        distance_transform(&mut to_inside, width, height);
+        distance_transform(&mut to_outside, width, height);
+
+        let pixels = inside
+            .iter()
+            .zip(to_inside.iter().zip(to_outside.iter()))
+            .map(|(&inside, (&to_inside, &to_outside))| {
+                // Pixel centers are half a pixel from the edge between them.
+                let distance = if inside {
+                    to_outside.sqrt() - 0.5
+                } else {
+                    0.5 - to_inside.sqrt()
+                };
+                let value = 128.0 + distance / spread * 127.0;
+                R8::new(value.round().clamp(0.0, 255.0) as u8)
+            })
+            .collect();
+        Image::from_vec(pixels, self.width(), self.height())
+    }
+}
+
+/// Transforms a grid of 0 for features and `FAR` otherwise into the squared distance to the
+/// nearest feature, by transforming columns and then rows.
+fn distance_transform(grid: &mut [f32], width: usize, height: usize) {
+    let length = width.max(height);
+    let mut f = vec![0.0; length];
+    let mut d = vec![0.0; length];
+    let mut v = vec![0; length];
+    let mut z = vec![0.0; length + 1];
+
+    for x in 0..width {
+        for y in 0..height {
+            f[y] = grid[y * width + x];
+        }
+        transform_1d(&f[..height], &mut d, &mut v, &mut z);
+        for y in 0..height {
+            grid[y * width + x] = d[y];
+        }
+    }
+    for row in grid.chunks_exact_mut(width) {
+        f[..width].copy_from_slice(row);
+        transform_1d(&f[..width], &mut d, &mut v, &mut z);
+        row.copy_from_slice(&d[..width]);
+    }
+}
+
+/// The one dimensional squared distance transform, which finds the lower envelope of the
+/// parabolas rooted at each sample.
+fn transform_1d(f: &[f32], d: &mut [f32], v: &mut [usize], z: &mut [f32]) {
+    let n = f.len();
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = -FAR;
+    z[1] = FAR;
+    for q in 1..n {
+        // Written without squaring the positions, which loses precision in f32 on large images.
+        let intersection = |p: usize| (q + p) as f32 / 2.0 + (f[q] - f[p]) / (2 * (q - p)) as f32;
+        let mut s = intersection(v[k]);
+        // The first boundary is effectively negative infinity, so k never drops below 0.
+        while s <= z[k] {
+            k -= 1;
+            s = intersection(v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = FAR;
+    }
+    k = 0;
+    for (q, d) in d.iter_mut().enumerate().take(n) {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let offset = q as f32 - v[k] as f32;
+        *d = offset * offset + f[v[k]];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::RGBA8;
+
+    #[test]
+    fn square() {
+        // A 3x3 opaque square in the middle of a 9x9 transparent image.
+        let mut image = Image::from_color(RGBA8::TRANSPARENT, 9, 9);
+        for y in 3..6 {
+            for x in 3..6 {
+                image.set(x, y, RGBA8::WHITE);
+            }
+        }
+        let sdf = image.signed_distance_field(Channel::Alpha, 4.0);
+        let value = |x, y| sdf.get(x, y).r;
+        // Centre is 1.5 pixels in, the edge pixels 0.5 pixels in, and their neighbours 0.5 out.
+        assert_eq!(value(4, 4), 176);
+        assert_eq!(value(3, 4), 144);
+        assert_eq!(value(2, 4), 112);
+        assert_eq!(value(0, 4), 49);
+        // Diagonal distances are Euclidean.
+        assert_eq!(value(1, 1), (128.0 + (0.5 - 8f32.sqrt()) / 4.0 * 127.0).round() as u8);
+        assert_eq!(value(0, 0), (128.0 + (0.5 - 18f32.sqrt()) / 4.0 * 127.0).round() as u8);
+    }
+
+    #[test]
+    fn large_rows() {
+        // Past 4096 samples the squared positions no longer fit in an f32 exactly, which used to
+        // move the boundary between these two parabolas to 9000 instead of 9002.
+        let n = 9100;
+        let mut f = vec![FAR; n];
+        f[9000] = 0.0;
+        f[9001] = 3.0;
+        let (mut d, mut v, mut z) = (vec![0.0; n], vec![0; n], vec![0.0; n + 1]);
+        transform_1d(&f, &mut d, &mut v, &mut z);
+        assert_eq!(&d[8998..9004], [4.0, 1.0, 0.0, 1.0, 4.0, 7.0]);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::color::RGBA8;
+use crate::graphics::TextureFiltering;
+use crate::image::{Image, ImageError, Packer, Rect};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+/// Sprites packed ahead of time into a single image, with the name and location of each sprite.
+/// Sprite sheets are meant to be built offline, saved with `Image::to_png` and `metadata`, then
+/// loaded at runtime with `graphics::SpriteAtlas`, so startup doesn't have to pack every sprite.
+///
+/// The metadata is plain text. The first line is `size` followed by the width and height of the
+/// sheet, and every following line is `sprite` followed by the x, y, width, and height of a
+/// sprite, then its name. Lines starting with `#` are comments.
+///
+/// ```text
+/// size 256 256
+/// sprite 4 4 16 16 player/idle
+/// sprite 28 4 16 32 door
+/// ```
+pub struct SpriteSheet {
+    image: Image<RGBA8>,
+    sprites: Vec<(String, Rect)>,
+}
+
+impl SpriteSheet {
+    /// Packs the sprites into a square sheet. Each sprite is padded the same way `TextureAtlas`
+    /// pads with the given filtering, so the sheet can be uploaded with that filtering without mip
+    /// map artifacts. Larger sprites are packed first. Fails with `ImageError::OutOfSpace` if the
+    /// sprites don't fit.
+    pub fn pack(
+        size: u32,
+        filtering: TextureFiltering,
+        sprites: &[(String, Image<RGBA8>)],
+    ) -> Result<SpriteSheet, ImageError> {
+        let padding = filtering.atlas_padding().unwrap_or(0);
+        let mut order: Vec<&(String, Image<RGBA8>)> = sprites.iter().collect();
+        order.sort_by(|(a_name, a), (b_name, b)| {
+            b.height().cmp(&a.height()).then(b.width().cmp(&a.width())).then(a_name.cmp(b_name))
+        });
+
+        let mut image = Image::from_color(RGBA8::TRANSPARENT, size, size);
+        let mut packer = Packer::new(size, size);
+        let mut packed = Vec::with_capacity(sprites.len());
+        for (name, sprite) in order {
+            let rect = packer
+                .pack(sprite.width() + padding * 2, sprite.height() + padding * 2)
+                .ok_or(ImageError::OutOfSpace)?;
+            if padding > 0 {
+                image.set_subsection(rect.x, rect.y, &sprite.pad_uniform(padding));
+            } else {
+                image.set_subsection(rect.x, rect.y, sprite);
+            }
+            packed.push((
+                name.clone(),
+                Rect::new(rect.x + padding, rect.y + padding, sprite.width(), sprite.height()),
+            ));
+        }
+        packed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(SpriteSheet {
+            image,
+            sprites: packed,
+        })
+    }
+
+    /// Gets the packed image.
+    pub fn image(&self) -> &Image<RGBA8> {
+        &self.image
+    }
+
+    /// Gets the name and location of every sprite in the sheet, sorted by name. Locations exclude
+    /// padding.
+    pub fn sprites(&self) -> &[(String, Rect)] {
+        &self.sprites
+    }
+
+    /// Writes the metadata describing where each sprite is in the sheet.
+    pub fn metadata(&self) -> String {
+        let mut output = format!("size {} {}\n", self.image.width(), self.image.height());
+        for (name, rect) in &self.sprites {
+            let _ = writeln!(output, "sprite {} {} {} {} {}", rect.x, rect.y, rect.w, rect.h, name);
+        }
+        output
+    }
+
+    /// Parses sprite sheet metadata. Malformed lines, and sprites outside of the sheet, fail with
+    /// `ImageError::InvalidData`.
+    pub fn parse_metadata(source: &str) -> Result<SpriteSheetMetadata, ImageError> {
+        let mut size = None;
+        let mut sprites = Vec::new();
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.splitn(6, ' ');
+            let kind = parts.next();
+            let mut number = || -> Result<u32, ImageError> {
+                parts.next().and_then(|part| part.parse().ok()).ok_or(ImageError::InvalidData)
+            };
+            match kind {
+                Some("size") if size.is_none() => {
+                    size = Some((number()?, number()?));
+                }
+                Some("sprite") => {
+                    let (width, height) = size.ok_or(ImageError::InvalidData)?;
+                    let rect = Rect::new(number()?, number()?, number()?, number()?);
+                    let name = parts.next().map(str::trim).filter(|name| !name.is_empty());
+                    let name = name.ok_or(ImageError::InvalidData)?;
+                    if rect.x as u64 + rect.w as u64 > width as u64
+                        || rect.y as u64 + rect.h as u64 > height as u64
+                    {
+                        return Err(ImageError::InvalidData);
+                    }
+                    sprites.push((String::from(name), rect));
+                }
+                _ => return Err(ImageError::InvalidData),
+            }
+        }
+        let (width, height) = size.ok_or(ImageError::InvalidData)?;
+        Ok(SpriteSheetMetadata {
+            width,
+            height,
+            sprites,
+        })
+    }
+}
+
+/// The parsed metadata of a sprite sheet.
+#[derive(Clone, Debug)]
+pub struct SpriteSheetMetadata {
+    /// The width of the sheet.
+    pub width: u32,
+    /// The height of the sheet.
+    pub height: u32,
+    /// The name and location of every sprite in the sheet.
+    pub sprites: Vec<(String, Rect)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_and_parse() {
+        let sprites = alloc::vec![
+            (String::from("small"), Image::from_color(RGBA8::RED, 2, 2)),
+            (String::from("big one"), Image::from_color(RGBA8::BLUE, 4, 6)),
+        ];
+        let sheet = SpriteSheet::pack(32, TextureFiltering::bilinear(1), &sprites).unwrap();
+        let metadata = SpriteSheet::parse_metadata(&sheet.metadata()).unwrap();
+        assert_eq!((metadata.width, metadata.height), (32, 32));
+        let parsed = metadata.sprites;
+        assert_eq!(parsed.len(), 2);
+        for ((name, rect), (expected_name, expected)) in parsed.iter().zip(sheet.sprites()) {
+            assert_eq!(name, expected_name);
+            assert_eq!((rect.x, rect.y, rect.w, rect.h), (expected.x, expected.y, expected.w, expected.h));
+        }
+        let (_, big) = &parsed[0];
+        assert_eq!(parsed[0].0, "big one");
+        assert_eq!((big.x, big.y, big.w, big.h), (2, 2, 4, 6));
+        assert_eq!(sheet.image().get(big.x, big.y), RGBA8::BLUE);
+        // The padding repeats the edge.
+        assert_eq!(sheet.image().get(big.x - 2, big.y - 2), RGBA8::BLUE);
+
+        assert!(SpriteSheet::pack(4, TextureFiltering::none(), &sprites).is_err());
+        assert!(SpriteSheet::parse_metadata("sprite 0 0 1 1 x").is_err());
+        assert!(SpriteSheet::parse_metadata("size 4 4\nsprite 2 2 4 4 x").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use super::format::checked_image;
+use super::{Image, ImageError};
+use crate::color::RGBA8;
+use alloc::vec::Vec;
+
+const HEADER_SIZE: usize = 18;
+
+struct Header {
+    id_length: usize,
+    color_map_type: u8,
+    image_type: u8,
+    color_map_first: usize,
+    color_map_length: usize,
+    color_map_depth: u8,
+    width: u32,
+    height: u32,
+    depth: u8,
+    descriptor: u8,
+}
+
+impl Header {
+    fn parse(bytes: &[u8]) -> Option<Header> {
+        let bytes = bytes.get(..HEADER_SIZE)?;
+        let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        Some(Header {
+            id_length: bytes[0] as usize,
+            color_map_type: bytes[1],
+            image_type: bytes[2],
+            color_map_first: u16_at(3) as usize,
+            color_map_length: u16_at(5) as usize,
+            color_map_depth: bytes[7],
+            width: u16_at(12) as u32,
+            height: u16_at(14) as u32,
+            depth: bytes[16],
+            descriptor: bytes[17],
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        let valid_type = match self.image_type {
+            1 | 9 => self.color_map_type == 1 && self.depth == 8,
+            2 | 10 => matches!(self.depth, 15 | 16 | 24 | 32),
+            3 | 11 => self.depth == 8,
+            _ => false,
+        };
+        let valid_map = match self.color_map_type {
+            0 => true,
+            1 => matches!(self.color_map_depth, 15 | 16 | 24 | 32),
+            _ => false,
+        };
+        valid_type && valid_map && self.width > 0 && self.height > 0 && self.descriptor & 0xc0 == 0
+    }
+
+    fn alpha_bits(&self) -> u8 {
+        self.descriptor & 0x0f
+    }
+}
+
+/// Returns true if the bytes start with a plausible TGA header.
+pub(crate) fn is_tga(bytes: &[u8]) -> bool {
+    Header::parse(bytes).is_some_and(|header| header.is_valid())
+}
+
+/// Reads a single little endian color of the given bit depth.
+fn read_color(bytes: &[u8], depth: u8, alpha_bits: u8) -> RGBA8 {
+    match depth {
+        8 => RGBA8::new(bytes[0], bytes[0], bytes[0], 255),
+        15 | 16 => {
+            let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+            let scale = |value: u16| ((value as u32 * 255 + 15) / 31) as u8;
+            let alpha = if depth == 16 && alpha_bits > 0 && value & 0x8000 == 0 {
+                0
+            } else {
+                255
+            };
+            RGBA8::new(scale((value >> 10) & 0x1f), scale((value >> 5) & 0x1f), scale(value & 0x1f), alpha)
+        }
+        24 => RGBA8::new(bytes[2], bytes[1], bytes[0], 255),
+        _ => RGBA8::new(
+            bytes[2],
+            bytes[1],
+            bytes[0],
+            if alpha_bits > 0 {
+                bytes[3]
+            } else {
+                255
+            },
+        ),
+    }
+}
+
+/// Decodes a TGA. Color mapped, true color, and grayscale images are supported, with or without
+/// RLE compression.
+pub fn read_tga(bytes: &[u8]) -> Result<Image<RGBA8>, ImageError> {
+    let header = Header::parse(bytes).ok_or(ImageError::InvalidData)?;
+    if !header.is_valid() {
+        return Err(ImageError::Unsupported);
+    }
+    super::format::check_dimensions(header.width, header.height)?;
+
+    let mut offset = HEADER_SIZE + header.id_length;
+    let mut palette = Vec::new();
+    if header.color_map_type == 1 {
+        let entry = (header.color_map_depth as usize).div_ceil(8);
+        let table =
+            bytes.get(offset..offset + header.color_map_length * entry).ok_or(ImageError::InvalidData)?;
+        palette =
+            table.chunks_exact(entry).map(|color| read_color(color, header.color_map_depth, 1)).collect();
+        offset += table.len();
+    }
+
+    let pixel_size = (header.depth as usize).div_ceil(8);
+    let count = (header.width * header.height) as usize;
+    let decode = |pixel: &[u8]| -> Result<RGBA8, ImageError> {
+        match header.image_type {
+            1 | 9 => {
+                let index = (pixel[0] as usize).checked_sub(header.color_map_first);
+                index.and_then(|index| palette.get(index).copied()).ok_or(ImageError::InvalidData)
+            }
+            _ => Ok(read_color(pixel, header.depth, header.alpha_bits())),
+        }
+    };
+
+    // Nothing is allocated from the header's size until the data is known to cover it, compressed
+    // pixels are collected as they decode instead.
+    let mut data = bytes.get(offset..).ok_or(ImageError::InvalidData)?;
+    let mut decoded = Vec::new();
+    if header.image_type >= 9 {
+        while decoded.len() < count {
+            let (&packet, rest) = data.split_first().ok_or(ImageError::InvalidData)?;
+            let length = (packet & 0x7f) as usize + 1;
+            if packet & 0x80 != 0 {
+                let pixel = decode(rest.get(..pixel_size).ok_or(ImageError::InvalidData)?)?;
+                decoded.extend(core::iter::repeat(pixel).take(length.min(count - decoded.len())));
+                data = &rest[pixel_size..];
+            } else {
+                let raw = rest.get(..length * pixel_size).ok_or(ImageError::InvalidData)?;
+                for pixel in raw.chunks_exact(pixel_size) {
+                    decoded.push(decode(pixel)?);
+                }
+                data = &rest[raw.len()..];
+            }
+        }
+        decoded.truncate(count);
+    } else {
+        let raw = data.get(..count * pixel_size).ok_or(ImageError::InvalidData)?;
+        decoded.reserve_exact(count);
+        for pixel in raw.chunks_exact(pixel_size) {
+            decoded.push(decode(pixel)?);
+        }
+    }
+
+    // Pixels are stored bottom to top and left to right unless the descriptor says otherwise.
+    let (width, height) = (header.width as usize, header.height as usize);
+    let right_to_left = header.descriptor & 0x10 != 0;
+    let top_to_bottom = header.descriptor & 0x20 != 0;
+    let mut pixels = Vec::with_capacity(count);
+    for y in 0..height {
+        let row = if top_to_bottom {
+            y
+        } else {
+            height - 1 - y
+        };
+        let row = &decoded[row * width..(row + 1) * width];
+        if right_to_left {
+            pixels.extend(row.iter().rev());
+        } else {
+            pixels.extend_from_slice(row);
+        }
+    }
+    checked_image(pixels, header.width, header.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(image_type: u8, width: u16, height: u16) -> Vec<u8> {
+        let mut bytes = alloc::vec![0, 0, image_type, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend_from_slice(&[24, 0x20]);
+        bytes
+    }
+
+    #[test]
+    fn raw_and_rle() {
+        let mut raw = header(2, 2, 1);
+        raw.extend_from_slice(&[0, 0, 255, 255, 0, 0]);
+        let image = read_tga(&raw).unwrap();
+        assert_eq!(image.as_slice(), [RGBA8::RED, RGBA8::BLUE]);
+
+        // A run longer than the image is clipped to it.
+        let mut rle = header(10, 2, 1);
+        rle.extend_from_slice(&[0x83, 0, 255, 0]);
+        assert_eq!(read_tga(&rle).unwrap().as_slice(), [RGBA8::GREEN, RGBA8::GREEN]);
+    }
+
+    #[test]
+    fn oversized_headers() {
+        // Headers claiming far more pixels than the data holds fail without allocating for them.
+        assert_eq!(read_tga(&header(2, 16384, 16384)).err(), Some(ImageError::InvalidData));
+        assert_eq!(read_tga(&header(10, 16384, 16384)).err(), Some(ImageError::InvalidData));
+        assert_eq!(read_tga(&header(2, 65535, 65535)).err(), Some(ImageError::InvalidDimensions));
+    }
+}
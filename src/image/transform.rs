@@ -0,0 +1,165 @@
+use crate::color::{ColorBlend, ColorDescriptor};
+use crate::image::Image;
+use alloc::{vec, vec::Vec};
+
+/// The tile size used when transposing, so reads and writes both stay within a few cache lines.
+const TRANSPOSE_BLOCK: u32 = 16;
+
+impl<T: ColorDescriptor> Image<T> {
+    /// Creates a copy of the image mirrored left to right.
+    pub fn flip_horizontal(&self) -> Image<T> {
+        let mut pixels = self.as_slice().to_vec();
+        for row in pixels.chunks_exact_mut(self.width() as usize) {
+            row.reverse();
+        }
+        Image::from_vec(pixels, self.width(), self.height())
+    }
+
+    /// Creates a copy of the image mirrored top to bottom.
+    pub fn flip_vertical(&self) -> Image<T> {
+        let mut pixels = Vec::with_capacity(self.as_slice().len());
+        for row in self.as_slice().chunks_exact(self.width() as usize).rev() {
+            pixels.extend_from_slice(row);
+        }
+        Image::from_vec(pixels, self.width(), self.height())
+    }
+
+    /// Creates a copy of the image mirrored across its main diagonal, so rows become columns. The
+    /// width and height are swapped. Other rotations are built on this, as it's done in small tiles
+    /// to stay cache friendly.
+    pub fn transpose(&self) -> Image<T> {
+        let (width, height) = (self.width(), self.height());
+        let source = self.as_slice();
+        let mut pixels = vec![T::default(); source.len()];
+        for block_y in (0..height).step_by(TRANSPOSE_BLOCK as usize) {
+            for block_x in (0..width).step_by(TRANSPOSE_BLOCK as usize) {
+                for y in block_y..(block_y + TRANSPOSE_BLOCK).min(height) {
+                    for x in block_x..(block_x + TRANSPOSE_BLOCK).min(width) {
+                        pixels[(x * height + y) as usize] = source[(y * width + x) as usize];
+                    }
+                }
+            }
+        }
+        Image::from_vec(pixels, height, width)
+    }
+
+    /// Creates a copy of the image rotated 90 degrees clockwise.
+    pub fn rotate_90(&self) -> Image<T> {
+        self.transpose().flip_horizontal()
+    }
+
+    /// Creates a copy of the image rotated 180 degrees.
+    pub fn rotate_180(&self) -> Image<T> {
+        let mut pixels = self.as_slice().to_vec();
+        pixels.reverse();
+        Image::from_vec(pixels, self.width(), self.height())
+    }
+
+    /// Creates a copy of the image rotated 270 degrees clockwise.
+    pub fn rotate_270(&self) -> Image<T> {
+        self.transpose().flip_vertical()
+    }
+
+    /// Creates a sub-image from a region of this image. (0, 0) is the top left of the image. The
+    /// region must be within the image, and can't be empty.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Image<T> {
+        let in_bounds = x.checked_add(width).is_some_and(|right| right <= self.width())
+            && y.checked_add(height).is_some_and(|bottom| bottom <= self.height());
+        assert!(in_bounds, "Crop region is out of bounds.");
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for row in y..y + height {
+            let start = self.index_for(x, row);
+            pixels.extend_from_slice(&self.as_slice()[start..start + width as usize]);
+        }
+        Image::from_vec(pixels, width, height)
+    }
+
+    /// Copies the source image onto this image with its top left at (x, y). Parts of the source
+    /// outside of this image are clipped, so the position may be negative.
+    pub fn blit(&mut self, x: i32, y: i32, source: &Image<T>) {
+        self.blit_with(x, y, source, |_, source| source);
+    }
+
+    /// Draws the source image onto this image with its top left at (x, y), combining each pair of
+    /// pixels with the blend function. The function is given the destination pixel, then the source
+    /// pixel. Parts of the source outside of this image are clipped, so the position may be
+    /// negative.
+    pub fn blit_with(&mut self, x: i32, y: i32, source: &Image<T>, blend: impl Fn(T, T) -> T) {
+        let left = x.max(0);
+        let top = y.max(0);
+        let right = (x as i64 + source.width() as i64).min(self.width() as i64) as i32;
+        let bottom = (y as i64 + source.height() as i64).min(self.height() as i64) as i32;
+        for target_y in top..bottom {
+            for target_x in left..right {
+                let color = source.get((target_x - x) as u32, (target_y - y) as u32);
+                let index = self.index_for(target_x as u32, target_y as u32);
+                self.set_indexed(index, blend(self.get_indexed(index), color));
+            }
+        }
+    }
+}
+
+impl<T: ColorBlend> Image<T> {
+    /// Draws the source image onto this image with its top left at (x, y), alpha blending the
+    /// source over this image. Both images use straight, non-premultiplied alpha, and colors without
+    /// alpha are copied as is. Parts of the source outside of this image are clipped, so the
+    /// position may be negative.
+    pub fn blit_alpha(&mut self, x: i32, y: i32, source: &Image<T>) {
+        self.blit_with(x, y, source, |destination, source| source.blend(destination, 1.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{R8, RGBA8};
+
+    fn image(width: u32, height: u32) -> Image<R8> {
+        Image::from_vec((0..width * height).map(|value| R8::new(value as u8)).collect(), width, height)
+    }
+
+    fn values(image: &Image<R8>) -> Vec<u8> {
+        image.as_slice().iter().map(|pixel| pixel.r).collect()
+    }
+
+    #[test]
+    fn rotations() {
+        // 0 1 2
+        // 3 4 5
+        let source = image(3, 2);
+        assert_eq!(values(&source.flip_horizontal()), [2, 1, 0, 5, 4, 3]);
+        assert_eq!(values(&source.flip_vertical()), [3, 4, 5, 0, 1, 2]);
+        assert_eq!(values(&source.transpose()), [0, 3, 1, 4, 2, 5]);
+        assert_eq!(values(&source.rotate_90()), [3, 0, 4, 1, 5, 2]);
+        assert_eq!(values(&source.rotate_180()), [5, 4, 3, 2, 1, 0]);
+        assert_eq!(values(&source.rotate_270()), [2, 5, 1, 4, 0, 3]);
+        assert_eq!(source.rotate_90().width(), 2);
+
+        let large = image(37, 21);
+        assert_eq!(values(&large.transpose().transpose()), values(&large));
+    }
+
+    #[test]
+    fn crop_and_blit() {
+        let source = image(3, 2);
+        assert_eq!(values(&source.crop(1, 0, 2, 2)), [1, 2, 4, 5]);
+
+        let mut target = Image::from_color(R8::new(9), 2, 2);
+        target.blit(-1, 1, &source);
+        assert_eq!(values(&target), [9, 9, 1, 2]);
+
+        let mut target = Image::from_color(RGBA8::RED, 1, 1);
+        target.blit_alpha(0, 0, &Image::from_color(RGBA8::new(0, 0, 255, 128), 1, 1));
+        assert_eq!(target.as_slice(), [RGBA8::new(127, 0, 128, 255)]);
+
+        let mut target = Image::from_color(R8::new(9), 2, 1);
+        target.blit_alpha(1, 0, &source);
+        assert_eq!(values(&target), [9, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Crop region is out of bounds.")]
+    fn crop_overflow() {
+        image(3, 2).crop(1, 0, u32::MAX, 1);
+    }
+}
@@ -41,6 +41,22 @@ pub trait App: 'static + Sized {
     /// The log filter level the engine uses by default.
     const LOG_LEVEL: log::Level = log::Level::Trace;
 
+    /// The number of threads the engine uses to read and decode assets. Paths within a single read
+    /// request are spread across all of them.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Web:** Unused. Reads are handled by the browser.
+    const ASSET_WORKERS: usize = 2;
+
+    /// The name of the directory save data is stored in, within the platform's data directory. This
+    /// should be unique to your game.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Web:** Unused. Storage is unsupported.
+    const STORAGE_DIRECTORY: &'static str = "storm";
+
     /// Function to create the app from a context.
     /// # Arguments
     ///
@@ -1,3 +1,4 @@
+use core::time::Duration;
 use parking_lot::{Condvar, Mutex};
 
 /// Simple Mutex + Condvar wait notify primitive. Can be used for waiting without spinning. This
@@ -33,4 +34,15 @@ impl Signal {
         }
         self.cvar.wait(&mut lock);
     }
+
+    /// Parks the current thread until notified or until the timeout elapses. May spuriously wake on
+    /// its own.
+    pub fn wait_for(&self, timeout: Duration) {
+        let mut lock = self.mutex.lock();
+        if *lock {
+            *lock = false;
+            return;
+        }
+        self.cvar.wait_for(&mut lock, timeout);
+    }
 }